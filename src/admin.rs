@@ -0,0 +1,271 @@
+use crate::circuit_breaker::{CircuitBreaker, Trip};
+use crate::config::{RiskSettings, RiskSettingsUpdate};
+use crate::risk_manager::RiskManager;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::os::unix::fs::PermissionsExt;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, error, info, warn};
+
+// One line of the risk audit log
+#[derive(Debug, Serialize)]
+struct AuditEntry<'a> {
+    timestamp: String,
+    // admin_socket or cli
+    source: &'a str,
+    old: &'a RiskSettings,
+    new: &'a RiskSettings,
+    persisted: bool,
+}
+
+// Applies risk setting changes to the running RiskManager, optionally writes
+// them back to the config file, and appends each change to the audit log
+pub struct RiskControl {
+    risk_manager: Arc<RwLock<RiskManager>>,
+    config_path: String,
+    audit_log_path: String,
+    // Serializes config rewrites and audit appends
+    write_lock: Mutex<()>,
+}
+
+impl RiskControl {
+    pub fn new(risk_manager: Arc<RwLock<RiskManager>>, config_path: String, audit_log_path: String) -> Self {
+        Self {
+            risk_manager,
+            config_path,
+            audit_log_path,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub async fn settings(&self) -> RiskSettings {
+        self.risk_manager.read().await.settings().clone()
+    }
+
+    pub async fn update(&self, update: &RiskSettingsUpdate, persist: bool, source: &str) -> Result<RiskSettings> {
+        let _guard = self.write_lock.lock().await;
+        let mut risk_manager = self.risk_manager.write().await;
+        let old = risk_manager.settings().clone();
+        let mut new = old.clone();
+        update.apply_to(&mut new);
+        new.validate()?;
+        if new == old && !persist {
+            return Ok(new);
+        }
+
+        // Written first so a config that can't be saved leaves the bot unchanged
+        if persist {
+            persist_risk_settings(&self.config_path, &new)?;
+        }
+        risk_manager.update_settings(new.clone())?;
+        drop(risk_manager);
+
+        let entry = AuditEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            source,
+            old: &old,
+            new: &new,
+            persisted: persist,
+        };
+        if let Err(e) = append_audit(&self.audit_log_path, &entry).await {
+            error!("❌ Failed to write risk audit log {}: {}", self.audit_log_path, e);
+        }
+
+        info!("🛡️ Risk settings changed via {}{}", source, if persist { ", saved to config" } else { "" });
+        Ok(new)
+    }
+}
+
+// Rewrites only the [risk_settings] table, keeping the rest of the file and
+// its comments as they are
+fn persist_risk_settings(config_path: &str, settings: &RiskSettings) -> Result<()> {
+    let content = std::fs::read_to_string(config_path)?;
+    let mut document: toml_edit::DocumentMut = content.parse()?;
+    let table: toml_edit::DocumentMut = toml::to_string(settings)?.parse()?;
+
+    let risk_settings = document["risk_settings"]
+        .or_insert(toml_edit::table())
+        .as_table_mut()
+        .ok_or_else(|| anyhow::anyhow!("risk_settings in {} is not a table", config_path))?;
+    for (key, value) in table.iter() {
+        // Assigning the value rather than the item keeps trailing comments
+        match (risk_settings.get_mut(key).and_then(|item| item.as_value_mut()), value.as_value()) {
+            (Some(existing), Some(value)) => {
+                let decor = existing.decor().clone();
+                *existing = value.clone();
+                *existing.decor_mut() = decor;
+            }
+            _ => {
+                risk_settings.insert(key, value.clone());
+            }
+        }
+    }
+
+    // Written beside the config and renamed over it, so a crash can't truncate it
+    let tmp_path = format!("{}.tmp", config_path);
+    std::fs::write(&tmp_path, document.to_string())?;
+    std::fs::rename(&tmp_path, config_path)?;
+    Ok(())
+}
+
+async fn append_audit(path: &str, entry: &AuditEntry<'_>) -> Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(path).await?;
+    file.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+// Newline-delimited JSON over the admin socket, one response line per request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AdminRequest {
+    GetRisk,
+    UpdateRisk {
+        #[serde(default)]
+        update: RiskSettingsUpdate,
+        #[serde(default)]
+        persist: bool,
+    },
+    BreakerStatus,
+    Resume,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminResponse {
+    pub success: bool,
+    pub message: String,
+    pub settings: Option<RiskSettings>,
+    // The circuit breaker trip a status request found or a resume cleared
+    #[serde(default)]
+    pub trip: Option<Trip>,
+}
+
+// Local control socket for a running bot. Anyone who can open the socket can
+// change risk limits, so it relies on filesystem permissions.
+pub struct AdminServer {
+    socket_path: String,
+    risk_control: Arc<RiskControl>,
+    circuit_breaker: Arc<CircuitBreaker>,
+}
+
+impl AdminServer {
+    pub fn new(socket_path: String, risk_control: Arc<RiskControl>, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        Self {
+            socket_path,
+            risk_control,
+            circuit_breaker,
+        }
+    }
+
+    pub async fn start(self: Arc<Self>) -> Result<()> {
+        // A socket file left by a previous run would fail the bind
+        if std::path::Path::new(&self.socket_path).exists() {
+            std::fs::remove_file(&self.socket_path)?;
+        }
+        let listener = UnixListener::bind(&self.socket_path)?;
+        // Anyone who can connect can change the risk limits
+        std::fs::set_permissions(&self.socket_path, std::fs::Permissions::from_mode(0o600))?;
+        info!("🔧 Admin socket listening on {}", self.socket_path);
+
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let server = self.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = server.handle_connection(stream).await {
+                                debug!("🔧 Admin connection closed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("⚠️ Admin socket accept failed: {}", e),
+                }
+            }
+        });
+        Ok(())
+    }
+
+    async fn handle_connection(&self, stream: UnixStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            let response = match serde_json::from_str::<AdminRequest>(&line) {
+                Ok(request) => self.handle(request).await,
+                Err(e) => AdminResponse {
+                    success: false,
+                    message: format!("Invalid request: {}", e),
+                    settings: None,
+                    trip: None,
+                },
+            };
+            let mut out = serde_json::to_string(&response)?;
+            out.push('\n');
+            writer.write_all(out.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle(&self, request: AdminRequest) -> AdminResponse {
+        match request {
+            AdminRequest::GetRisk => AdminResponse {
+                success: true,
+                message: "Current risk settings".to_string(),
+                settings: Some(self.risk_control.settings().await),
+                trip: None,
+            },
+            AdminRequest::UpdateRisk { update, persist } => {
+                match self.risk_control.update(&update, persist, "admin_socket").await {
+                    Ok(settings) => AdminResponse {
+                        success: true,
+                        message: "Risk settings updated".to_string(),
+                        settings: Some(settings),
+                        trip: None,
+                    },
+                    Err(e) => AdminResponse {
+                        success: false,
+                        message: format!("Risk settings rejected: {}", e),
+                        settings: None,
+                        trip: None,
+                    },
+                }
+            }
+            AdminRequest::BreakerStatus => {
+                let trip = self.circuit_breaker.trip_status().await;
+                AdminResponse {
+                    success: true,
+                    message: if trip.is_some() { "Auto-trading halted" } else { "Auto-trading active" }.to_string(),
+                    settings: None,
+                    trip,
+                }
+            }
+            AdminRequest::Resume => {
+                let trip = self.circuit_breaker.resume("admin_socket").await;
+                AdminResponse {
+                    success: true,
+                    message: if trip.is_some() { "Auto-trading resumed" } else { "Auto-trading was not halted" }.to_string(),
+                    settings: None,
+                    trip,
+                }
+            }
+        }
+    }
+}
+
+pub async fn send_admin_request(socket_path: &str, request: &AdminRequest) -> Result<AdminResponse> {
+    let stream = UnixStream::connect(socket_path).await?;
+    let (reader, mut writer) = stream.into_split();
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+
+    let response = BufReader::new(reader)
+        .lines()
+        .next_line()
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Admin socket closed without a response"))?;
+    Ok(serde_json::from_str(&response)?)
+}
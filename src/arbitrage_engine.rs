@@ -1,19 +1,43 @@
 use crate::{
+    blockhash_manager::BlockhashManager,
+    circuit_breaker::{CircuitBreaker, ExecutionOutcome, TripReason},
+    compute_budget::{route_key, ComputeBudgetPlanner},
     config::Config,
+    confirmation_tracker::{ConfirmationTracker, ConfirmedTransaction},
     dex_monitor::DexMonitor,
-    risk_manager::RiskManager,
+    risk_manager::{RiskManager, TradeExposure},
     portfolio_manager::PortfolioManager,
-    jito_client::JitoClient,
-    jupiter_client::JupiterClient,
+    jito_client::{BundleStatus, JitoClient, SimulationGuard},
+    lookup_table_manager::LookupTableManager,
+    jupiter_client::{JupiterClient, JupiterQuoteRequest, JupiterQuoteResponse, JupiterSwapRequest},
     monitoring::MonitoringService,
+    route_analysis::{analyze_route, MintPrice},
+    rpc_pool::RpcPool,
+    stranded_inventory::{unwind_decision, StrandedInventory, StrandedPosition},
+    token_screen::TokenScreener,
+    transaction_builder::TransactionBuilder,
+    transaction_meta::{fetch_balance_change, BalanceChange},
     types::{
         ArbitrageOpportunity, PriceData, TradeRequest, TradeResponse,
         EnhancedArbitrageOpportunity, JupiterQuote, SwapRequest, SwapResponse,
-        ExecutionMethod, DexPrice, ArbitrageError
+        ExecutionMethod, ExecutionPath, DexPrice, ArbitrageError, SwapMode,
+        JupiterError, JupiterErrorAction
     },
+    utils::{associated_token_address, parse_keypair},
 };
 use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use metrics::{counter, gauge, histogram};
+use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::VersionedTransaction,
+};
+use std::str::FromStr;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{info, warn, error, debug};
 use uuid::Uuid;
@@ -28,7 +52,20 @@ pub struct ArbitrageEngine {
     jito_client: Option<Arc<JitoClient>>,
     jupiter_client: Option<Arc<JupiterClient>>,
     monitoring: Arc<MonitoringService>,
+    rpc_pool: Arc<RpcPool>,
+    blockhash_manager: Arc<BlockhashManager>,
+    lookup_table_manager: Arc<LookupTableManager>,
+    compute_budget: Arc<ComputeBudgetPlanner>,
+    transaction_builder: Arc<TransactionBuilder>,
+    confirmation_tracker: Arc<ConfirmationTracker>,
+    circuit_breaker: Arc<CircuitBreaker>,
+    token_screener: Arc<TokenScreener>,
+    stranded_inventory: Arc<StrandedInventory>,
     is_running: Arc<RwLock<bool>>,
+    // Token pairs skipped until the given instant after Jupiter rejected them
+    pair_cooldowns: Arc<RwLock<HashMap<String, Instant>>>,
+    // Opportunities from recent scans by id, the only ones trades can execute
+    opportunities: Arc<RwLock<HashMap<String, ArbitrageOpportunity>>>,
 }
 
 impl ArbitrageEngine {
@@ -41,6 +78,43 @@ impl ArbitrageEngine {
         jupiter_client: Option<Arc<JupiterClient>>,
         monitoring: Arc<MonitoringService>,
     ) -> Self {
+        let rpc_pool = Arc::new(RpcPool::new(&config.rpc_endpoints, CommitmentConfig::confirmed()));
+        let commitment = CommitmentLevel::from_str(&config.execution.commitment).unwrap_or_else(|_| {
+            warn!("⚠️ Unknown commitment {:?}, using confirmed", config.execution.commitment);
+            CommitmentLevel::Confirmed
+        });
+        let blockhash_manager = Arc::new(BlockhashManager::new(
+            rpc_pool.clone(),
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(config.execution.blockhash_refresh_ms),
+        ));
+        let lookup_table_manager = Arc::new(LookupTableManager::new(rpc_pool.clone(), config.lookup_tables.clone()));
+        let compute_budget = Arc::new(ComputeBudgetPlanner::new(
+            rpc_pool.clone(),
+            config.compute_budget.clone(),
+            config.execution.compute_unit_price_micro_lamports,
+        ));
+        let transaction_builder = Arc::new(TransactionBuilder::new(
+            rpc_pool.clone(),
+            blockhash_manager.clone(),
+            lookup_table_manager.clone(),
+            compute_budget.clone(),
+        ));
+        let confirmation_tracker = Arc::new(ConfirmationTracker::new(
+            rpc_pool.clone(),
+            blockhash_manager.clone(),
+            config.rpc_endpoints.ws_url.clone(),
+            CommitmentConfig { commitment },
+            Duration::from_millis(config.execution.rebroadcast_interval_ms),
+            Duration::from_millis(config.execution.confirm_timeout_ms),
+        ));
+        let circuit_breaker = Arc::new(CircuitBreaker::new(config.circuit_breaker.clone()));
+        let token_screener = Arc::new(TokenScreener::new(
+            rpc_pool.clone(),
+            Duration::from_millis(config.token_screening.cache_ttl_ms),
+        ));
+        let stranded_inventory = Arc::new(StrandedInventory::new(config.unwind.state_path.clone()));
+
         Self {
             config,
             dex_monitor,
@@ -49,7 +123,18 @@ impl ArbitrageEngine {
             jito_client,
             jupiter_client,
             monitoring,
+            rpc_pool,
+            blockhash_manager,
+            lookup_table_manager,
+            compute_budget,
+            transaction_builder,
+            confirmation_tracker,
+            circuit_breaker,
+            token_screener,
+            stranded_inventory,
             is_running: Arc::new(RwLock::new(false)),
+            pair_cooldowns: Arc::new(RwLock::new(HashMap::new())),
+            opportunities: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -59,6 +144,10 @@ impl ArbitrageEngine {
         drop(running);
 
         info!("🚀 Starting arbitrage engine");
+        self.rpc_pool.start().await;
+        self.blockhash_manager.start().await;
+        // Positions left by the last run keep their mints blocked until sold
+        self.sync_stranded_mints().await;
         
         // Start the main arbitrage loop
         let engine_clone = self.clone_for_task();
@@ -68,12 +157,35 @@ impl ArbitrageEngine {
             }
         });
 
+        if self.config.circuit_breaker.enabled {
+            let engine_clone = self.clone_for_task();
+            tokio::spawn(async move {
+                engine_clone.circuit_breaker_loop().await;
+            });
+        }
+
+        if self.config.unwind.enabled {
+            let engine_clone = self.clone_for_task();
+            tokio::spawn(async move {
+                engine_clone.unwind_loop().await;
+            });
+        }
+
+        if self.config.lookup_tables.enabled {
+            let engine_clone = self.clone_for_task();
+            tokio::spawn(async move {
+                engine_clone.lookup_table_loop().await;
+            });
+        }
+
         Ok(())
     }
 
     pub async fn stop(&self) -> Result<()> {
         let mut running = self.is_running.write().await;
         *running = false;
+        self.blockhash_manager.stop().await;
+        self.rpc_pool.stop().await;
         info!("🛑 Stopping arbitrage engine");
         Ok(())
     }
@@ -107,12 +219,18 @@ impl ArbitrageEngine {
             // Extract token mints (simplified - in real implementation, you'd have a mapping)
             let (input_mint, output_mint) = self.extract_token_mints(&token_pair)?;
             
+            if self.is_pair_cooling_down(&token_pair).await {
+                debug!("⏸️ Skipping {} while on Jupiter cooldown", token_pair);
+                continue;
+            }
+
             // Get Jupiter quote if enabled
             let jupiter_quote = if self.config.jupiter.enabled && self.jupiter_client.is_some() {
-                match self.get_jupiter_quote(&input_mint, &output_mint, max_amount as u64).await {
-                    Ok(quote) => Some(quote),
+                match self.get_jupiter_quote(&input_mint, &output_mint, max_amount as u64, SwapMode::ExactIn).await {
+                    Ok(quote) => self.check_route(&token_pair, quote).await,
                     Err(e) => {
                         warn!("⚠️ Failed to get Jupiter quote for {}: {}", token_pair, e);
+                        self.handle_jupiter_error(&token_pair, &e).await?;
                         None
                     }
                 }
@@ -193,6 +311,7 @@ impl ArbitrageEngine {
         debug!("🔍 Scanning for arbitrage opportunities");
         
         let prices = self.dex_monitor.get_all_prices().await?;
+        let min_liquidity = self.risk_manager.read().await.settings().min_liquidity;
         let mut opportunities = Vec::new();
 
         // Group prices by token pair
@@ -200,6 +319,9 @@ impl ArbitrageEngine {
             std::collections::HashMap::new();
         
         for price in prices {
+            if price.liquidity < min_liquidity {
+                continue;
+            }
             price_groups.entry(price.token_pair.clone()).or_default().push(price);
         }
 
@@ -217,6 +339,9 @@ impl ArbitrageEngine {
             let highest_price = &sorted_prices[sorted_prices.len() - 1];
 
             let profit_percentage = ((highest_price.price - lowest_price.price) / lowest_price.price) * 100.0;
+            if !self.circuit_breaker.check_price_spread(&token_pair, profit_percentage).await {
+                continue;
+            }
             
             if profit_percentage >= min_profit_percentage {
                 let estimated_profit = (highest_price.price - lowest_price.price) * max_amount;
@@ -249,42 +374,98 @@ impl ArbitrageEngine {
         // Sort by profit percentage
         opportunities.sort_by(|a, b| b.profit_percentage.partial_cmp(&a.profit_percentage).unwrap());
 
+        let oldest = Utc::now().timestamp_millis() - self.config.trading.execution_timeout_ms as i64;
+        let mut known = self.opportunities.write().await;
+        known.retain(|_, opportunity| opportunity.timestamp >= oldest);
+        for opportunity in &opportunities {
+            known.insert(opportunity.id.clone(), opportunity.clone());
+        }
+        drop(known);
+
         info!("✅ Found {} arbitrage opportunities", opportunities.len());
         Ok(opportunities)
     }
 
     pub async fn execute_trade(&self, request: TradeRequest) -> Result<TradeResponse> {
+        let opportunity = self.get_opportunity_by_id(&request.opportunity_id).await?;
+        self.execute_opportunity(request, opportunity).await
+    }
+
+    // Trades `opportunity` as scanned. Refused once it's older than
+    // execution_timeout_ms, since its prices no longer mean anything.
+    async fn execute_opportunity(&self, request: TradeRequest, opportunity: ArbitrageOpportunity) -> Result<TradeResponse> {
         info!("💼 Executing trade for opportunity: {}", request.opportunity_id);
-        
+
+        let age_ms = Utc::now().timestamp_millis() - opportunity.timestamp;
+        if age_ms > self.config.trading.execution_timeout_ms as i64 {
+            return Err(anyhow::anyhow!("Opportunity {} expired {} ms ago", opportunity.id, age_ms));
+        }
+        let (base_mint, quote_mint) = self.extract_token_mints(&opportunity.token_pair)?;
+        let mints = if self.config.token_screening.enabled {
+            let mints = [Pubkey::from_str(&base_mint)?, Pubkey::from_str(&quote_mint)?];
+            self.token_screener.profiles(&mints).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut dexes = vec![opportunity.buy_dex.clone()];
+        let mut pools = vec![opportunity.buy_pool.clone()];
+        if opportunity.sell_dex != opportunity.buy_dex {
+            dexes.push(opportunity.sell_dex.clone());
+        }
+        if opportunity.sell_pool != opportunity.buy_pool {
+            pools.push(opportunity.sell_pool.clone());
+        }
+        // Quote is what we hold anyway, base is inventory between the legs
+        let notional = if self.config.exposure_limits.enabled {
+            self.quote_usd_value(&quote_mint, request.amount).await?
+        } else {
+            0.0
+        };
+        let exposure = TradeExposure {
+            notional,
+            mints: vec![base_mint.clone()],
+            dexes,
+            pools,
+        };
+
         // Risk check
-        let risk_manager = self.risk_manager.read().await;
-        if !risk_manager.can_execute_trade(&request).await? {
+        let mut risk_manager = self.risk_manager.write().await;
+        if let Err(rejection) = risk_manager.can_execute_trade(&request, &mints, &exposure) {
             return Ok(TradeResponse {
                 transaction_id: "".to_string(),
                 success: false,
-                error_message: "Risk check failed".to_string(),
+                error_message: format!("Risk check failed: {}", rejection),
                 actual_profit: 0.0,
                 gas_used: 0.0,
                 execution_time: 0,
                 bundle_id: "".to_string(),
+                landed_slot: 0,
+                bundle_status: "".to_string(),
+                execution_path: "".to_string(),
             });
         }
+        risk_manager.record_trade();
+        let open_trade = risk_manager.open_trade(exposure);
         drop(risk_manager);
 
         let start_time = std::time::Instant::now();
 
-        // Get opportunity details (in real implementation, this would be from a database)
-        let opportunity = self.get_opportunity_by_id(&request.opportunity_id).await?;
-        
         // Build and execute transaction
-        let transaction_result = if request.use_jito && self.jito_client.is_some() {
-            self.execute_jito_trade(&request, &opportunity).await?
-        } else {
-            self.execute_regular_trade(&request, &opportunity).await?
-        };
+        let transaction_result = self.execute_with_policy(&request, &opportunity).await;
+        drop(open_trade);
+        let (transaction_result, outcome) = transaction_result?;
 
         let execution_time = start_time.elapsed().as_millis() as i64;
 
+        // Shortfall against the scan's estimate, relative to the notional traded
+        let notional = opportunity.buy_price * request.amount;
+        let realized_slippage = (outcome == ExecutionOutcome::Filled && notional > 0.0)
+            .then(|| (opportunity.estimated_profit - transaction_result.actual_profit).max(0.0) / notional * 100.0);
+        self.circuit_breaker.record_trade(outcome, realized_slippage).await;
+
+        self.record_pnl(&quote_mint, transaction_result.actual_profit, transaction_result.gas_used).await;
+
         // Update monitoring metrics
         self.monitoring.record_trade_execution(
             transaction_result.success,
@@ -295,6 +476,77 @@ impl ArbitrageEngine {
         Ok(transaction_result)
     }
 
+    pub fn circuit_breaker(&self) -> Arc<CircuitBreaker> {
+        self.circuit_breaker.clone()
+    }
+
+    // Trips the breaker when the wallet runs low on SOL or every RPC endpoint
+    // falls behind
+    async fn circuit_breaker_loop(&self) {
+        let mut interval = tokio::time::interval(
+            Duration::from_millis(self.config.circuit_breaker.check_interval_ms)
+        );
+        let wallet = match Pubkey::from_str(&self.config.wallet.public_key) {
+            Ok(wallet) => Some(wallet),
+            Err(e) => {
+                warn!("⚠️ Invalid wallet public key, balance checks disabled: {}", e);
+                None
+            }
+        };
+
+        loop {
+            interval.tick().await;
+            if !*self.is_running.read().await {
+                break;
+            }
+
+            if let Some(wallet) = &wallet {
+                match self.rpc_pool.read(|rpc| async move { rpc.get_balance(wallet).await }).await {
+                    Ok(lamports) => {
+                        let balance = lamports as f64 / 1e9;
+                        gauge!("wallet_sol_balance").set(balance);
+                        self.circuit_breaker.check_balance(balance, self.config.wallet.min_sol_balance).await;
+                    }
+                    Err(e) => warn!("⚠️ Failed to fetch wallet balance: {}", e),
+                }
+            }
+            self.circuit_breaker.check_slot_lag(self.rpc_pool.best_slot_lag().await).await;
+        }
+    }
+
+    async fn lookup_table_loop(&self) {
+        let mut interval = tokio::time::interval(
+            Duration::from_millis(self.config.lookup_tables.maintenance_interval_ms)
+        );
+        // The first tick fires immediately, before any routes have been seen
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            if !*self.is_running.read().await {
+                break;
+            }
+            if let Err(e) = self.maintain_lookup_tables().await {
+                warn!("⚠️ Lookup table maintenance failed: {}", e);
+            }
+        }
+    }
+
+    // Adds frequently used route accounts to our lookup tables, paid for and
+    // owned by the configured wallet
+    async fn maintain_lookup_tables(&self) -> Result<()> {
+        let authority = parse_keypair(&self.config.wallet.private_key)?;
+        let owner = authority.pubkey().to_string();
+        for update in self.lookup_table_manager.plan_updates(&authority.pubkey()).await? {
+            let prepared = self.transaction_builder.compile_and_sign(&authority, &update.instructions, &[]).await?;
+            let confirmed = self.confirmation_tracker.send_and_track(&prepared, &owner, WSOL_MINT).await?;
+            info!("📇 Added {} accounts to lookup table {}", update.addresses.len(), update.table);
+            counter!("lookup_table_extensions_total").increment(1);
+            self.lookup_table_manager.apply(&update, confirmed.slot).await;
+        }
+        Ok(())
+    }
+
     async fn arbitrage_loop(&self) -> Result<()> {
         let mut interval = tokio::time::interval(
             std::time::Duration::from_millis(self.config.trading.scan_interval_ms)
@@ -308,27 +560,37 @@ impl ArbitrageEngine {
                 break;
             }
 
+            // Read each scan, so changes through the admin socket apply right away
+            let settings = self.risk_manager.read().await.settings().clone();
+
             // Scan for opportunities
             let opportunities = self.scan_opportunities(
-                self.config.risk_settings.min_profit_threshold,
-                self.config.risk_settings.max_position_size,
+                settings.min_profit_threshold,
+                settings.max_position_size,
             ).await?;
 
-            // Execute profitable trades if auto-trading is enabled
-            if self.config.trading.enable_auto_trading {
+            // Execute profitable trades if auto-trading is enabled. Trades run
+            // through Jupiter routes, so without it the bot only scans.
+            if self.config.trading.enable_auto_trading && settings.use_jupiter_for_execution {
                 for opportunity in opportunities {
+                    if let Some(trip) = self.circuit_breaker.trip_status().await {
+                        debug!("⏸️ Auto-trading halted since {}: {}", trip.tripped_at, trip.reason);
+                        break;
+                    }
                     if opportunity.is_profitable {
                         let trade_request = TradeRequest {
                             opportunity_id: opportunity.id.clone(),
                             amount: opportunity.max_amount,
                             private_key: self.config.wallet.private_key.clone(),
-                            max_slippage: self.config.risk_settings.max_slippage,
+                            max_slippage: settings.max_slippage,
                             priority_fee: 1000, // Default priority fee
                             use_jito: self.jito_client.is_some(),
-                            jito_tip: "100000".to_string(), // 0.0001 SOL
+                            jito_tip: 0, // Sized by the Jito tip strategy
                         };
 
-                        match self.execute_trade(trade_request).await {
+                        // Claimed, so an ExecuteTrade call can't run it a second time
+                        self.opportunities.write().await.remove(&opportunity.id);
+                        match self.execute_opportunity(trade_request, opportunity).await {
                             Ok(response) => {
                                 if response.success {
                                     info!("✅ Trade executed successfully: {}", response.transaction_id);
@@ -354,26 +616,69 @@ impl ArbitrageEngine {
         Ok(0.005) // $0.005 estimated gas cost
     }
 
+    // Takes a scanned opportunity, so each is executed at most once. Unknown
+    // ids are refused rather than traded on guessed prices.
     async fn get_opportunity_by_id(&self, id: &str) -> Result<ArbitrageOpportunity> {
-        // In a real implementation, this would fetch from a database
-        // For now, return a mock opportunity
-        Ok(ArbitrageOpportunity {
-            id: id.to_string(),
-            token_pair: "SOL/USDC".to_string(),
-            buy_dex: "Raydium".to_string(),
-            sell_dex: "Orca".to_string(),
-            buy_price: 100.0,
-            sell_price: 101.0,
-            profit_percentage: 1.0,
-            estimated_profit: 10.0,
-            max_amount: 1000.0,
-            gas_cost: 0.005,
-            timestamp: Utc::now().timestamp_millis(),
-            buy_pool: "pool_address_1".to_string(),
-            sell_pool: "pool_address_2".to_string(),
-            slippage: 0.5,
-            is_profitable: true,
-        })
+        self.opportunities.write().await.remove(id)
+            .ok_or_else(|| anyhow::anyhow!("Opportunity {} not found", id))
+    }
+
+    // Tries Jito up to execution.jito_attempts times, then falls back to an RPC
+    // send if enabled. A simulation rejection ends the trade on either path.
+    // Bundles are atomic, so one that didn't land left nothing on chain.
+    async fn execute_with_policy(
+        &self,
+        request: &TradeRequest,
+        opportunity: &ArbitrageOpportunity,
+    ) -> Result<(TradeResponse, ExecutionOutcome)> {
+        let policy = &self.config.execution;
+
+        if request.use_jito && self.jito_client.is_some() {
+            let attempts = policy.jito_attempts.max(1);
+            let mut last_failure = None;
+
+            for attempt in 1..=attempts {
+                match self.execute_jito_trade(request, opportunity).await {
+                    Ok(response) if response.success => {
+                        record_execution(ExecutionPath::Jito, "landed");
+                        return Ok((response, ExecutionOutcome::Filled));
+                    }
+                    Ok(response) => {
+                        warn!("⚠️ Jito attempt {}/{} for {}: {}",
+                              attempt, attempts, request.opportunity_id, response.error_message);
+                        record_execution(ExecutionPath::Jito, "dropped");
+                        last_failure = Some(response);
+                    }
+                    Err(e) if is_simulation_rejection(&e) => {
+                        warn!("🧪 {} not sent: {}", request.opportunity_id, e);
+                        record_execution(ExecutionPath::Jito, "rejected");
+                        return Ok((failed_trade_response(e.to_string(), ExecutionPath::Jito), ExecutionOutcome::NotLanded));
+                    }
+                    Err(e) => {
+                        warn!("⚠️ Jito attempt {}/{} for {} errored: {}",
+                              attempt, attempts, request.opportunity_id, e);
+                        record_execution(ExecutionPath::Jito, "error");
+                        last_failure = Some(failed_trade_response(e.to_string(), ExecutionPath::Jito));
+                    }
+                }
+            }
+
+            if !policy.rpc_fallback {
+                let response = last_failure.unwrap_or_else(|| {
+                    failed_trade_response("Jito attempts exhausted".to_string(), ExecutionPath::Jito)
+                });
+                return Ok((response, ExecutionOutcome::NotLanded));
+            }
+            info!("↪️ Falling back to RPC send for {}", request.opportunity_id);
+        }
+
+        let result = self.execute_rpc_trade(request, opportunity).await;
+        match &result {
+            Ok((_, ExecutionOutcome::Filled)) => record_execution(ExecutionPath::Rpc, "landed"),
+            Ok(_) => record_execution(ExecutionPath::Rpc, "failed"),
+            Err(_) => record_execution(ExecutionPath::Rpc, "error"),
+        }
+        result
     }
 
     async fn execute_jito_trade(
@@ -383,69 +688,641 @@ impl ArbitrageEngine {
     ) -> Result<TradeResponse> {
         if let Some(jito_client) = &self.jito_client {
             // Build Jito bundle transaction
-            let bundle_id = jito_client.submit_bundle(&request, opportunity).await?;
-            
+            let payer = parse_keypair(&request.private_key)?;
+            let (legs, expected_profit, last_valid_block_height) =
+                self.build_jupiter_legs(&payer.pubkey(), request, opportunity).await?;
+            let (_, quote_mint) = self.extract_token_mints(&opportunity.token_pair)?;
+            let expected_profit_lamports = self.quote_amount_to_lamports(&quote_mint, expected_profit).await?;
+            let tip_lamports = if request.jito_tip > 0 {
+                let tip_lamports = jito_client.clamp_tip(request.jito_tip);
+                if tip_lamports >= expected_profit_lamports {
+                    return Err(ArbitrageError::SimulationRejected(format!(
+                        "tip of {} lamports is not below the expected profit of {}",
+                        tip_lamports, expected_profit_lamports
+                    )).into());
+                }
+                tip_lamports
+            } else {
+                jito_client.suggest_tip(expected_profit_lamports).await?
+            };
+
+            histogram!("jito_tip_lamports").record(tip_lamports as f64);
+            histogram!("jito_expected_profit_lamports").record(expected_profit_lamports as f64);
+            histogram!("jito_tip_profit_ratio").record(tip_lamports as f64 / expected_profit_lamports as f64);
+
+            // Simulation must show at least the configured net gain, in quote units
+            let native = quote_mint == WSOL_MINT;
+            let min_net_lamports = self.config.jito.min_simulated_net_lamports;
+            let min_net = if native {
+                min_net_lamports as i128
+            } else {
+                // The token delta doesn't include the tip, so the threshold has to cover it
+                let lamports_per_unit = self.lamports_per_quote_unit(&quote_mint).await?;
+                ((min_net_lamports + tip_lamports as i64) as f64 / lamports_per_unit).ceil() as i128
+            };
+            let guard = SimulationGuard {
+                token_account: associated_token_address(&payer.pubkey(), &Pubkey::from_str(&quote_mint)?),
+                native,
+                min_net,
+            };
+
+            let bundle_id = jito_client.submit_bundle(&payer, legs, tip_lamports, Some(&guard)).await?;
+            let expiry = self.blockhash_manager.expiry_watch(last_valid_block_height);
+            let outcome = jito_client.wait_for_bundle(&bundle_id, Some(expiry)).await?;
+
+            if outcome.status != BundleStatus::Landed {
+                return Ok(TradeResponse {
+                    transaction_id: format!("jito_{}", bundle_id),
+                    success: false,
+                    error_message: format!("Jito bundle {}", outcome.status),
+                    actual_profit: 0.0,
+                    gas_used: 0.0,
+                    execution_time: 0,
+                    bundle_id,
+                    landed_slot: 0,
+                    bundle_status: outcome.status.to_string(),
+                    execution_path: ExecutionPath::Jito.to_string(),
+                });
+            }
+
+            let signatures = outcome.transactions
+                .iter()
+                .map(|s| Signature::from_str(s))
+                .collect::<Result<Vec<_>, _>>()?;
+            let (actual_profit, change) = self.realized_change(&signatures, &payer.pubkey(), &quote_mint, tip_lamports).await?;
+            let gas_used = (change.fee_lamports + tip_lamports) as f64 / 1e9;
+
+            // Tips are only paid when the bundle lands
+            counter!("jito_tip_spent_lamports_total").increment(tip_lamports);
+            counter!("jito_expected_profit_lamports_total").increment(expected_profit_lamports);
+
+            info!("💰 Bundle {} landed in slot {:?}: profit {:.6}, fees+tip {:.6} SOL",
+                  bundle_id, outcome.landed_slot, actual_profit, gas_used);
+
             Ok(TradeResponse {
-                transaction_id: format!("jito_{}", bundle_id),
+                transaction_id: outcome.transactions.first().cloned().unwrap_or_else(|| format!("jito_{}", bundle_id)),
                 success: true,
                 error_message: "".to_string(),
-                actual_profit: opportunity.estimated_profit,
-                gas_used: opportunity.gas_cost,
+                actual_profit,
+                gas_used,
                 execution_time: 0,
                 bundle_id,
+                landed_slot: outcome.landed_slot.unwrap_or_default(),
+                bundle_status: outcome.status.to_string(),
+                execution_path: ExecutionPath::Jito.to_string(),
             })
         } else {
             Err(anyhow::anyhow!("Jito client not available"))
         }
     }
 
-    async fn execute_regular_trade(
+    // Quotes for both legs: buy the base token on buy_dex, then sell the
+    // worst-case fill of that buy on sell_dex. Also returns the quote-mint input.
+    async fn quote_legs(
         &self,
         request: &TradeRequest,
         opportunity: &ArbitrageOpportunity,
-    ) -> Result<TradeResponse> {
-        // Build and execute regular Solana transaction
-        // This is a simplified implementation
-        let transaction_id = format!("tx_{}", Uuid::new_v4());
-        
-        Ok(TradeResponse {
-            transaction_id,
+    ) -> Result<(JupiterQuote, JupiterQuote, u64)> {
+        let jupiter_client = self.jupiter_client.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Jupiter client not available"))?;
+
+        let (base_mint, quote_mint) = self.extract_token_mints(&opportunity.token_pair)?;
+        let amount_in = (request.amount * 10f64.powi(token_decimals(&quote_mint)? as i32)) as u64;
+        let settings = self.risk_manager.read().await.settings().clone();
+        let slippage_bps = ((request.max_slippage * 100.0) as u16).min(settings.jupiter_slippage_bps);
+
+        let buy_quote = jupiter_client.get_quote(JupiterQuoteRequest {
+            input_mint: quote_mint.clone(),
+            output_mint: base_mint.clone(),
+            amount: amount_in,
+            slippage_bps,
+            swap_mode: Some(SwapMode::ExactIn),
+            dexes: Some(vec![opportunity.buy_dex.clone()]),
+            exclude_dexes: None,
+            platform_fee_bps: None,
+            max_accounts: Some(self.config.jupiter.max_route_accounts),
+        }).await?;
+        check_price_impact(&buy_quote, settings.max_price_impact_pct)?;
+        let sell_quote = jupiter_client.get_quote(JupiterQuoteRequest {
+            input_mint: base_mint,
+            output_mint: quote_mint,
+            amount: buy_quote.min_out_amount(),
+            slippage_bps,
+            swap_mode: Some(SwapMode::ExactIn),
+            dexes: Some(vec![opportunity.sell_dex.clone()]),
+            exclude_dexes: None,
+            platform_fee_bps: None,
+            max_accounts: Some(self.config.jupiter.max_route_accounts),
+        }).await?;
+        check_price_impact(&sell_quote, settings.max_price_impact_pct)?;
+
+        Ok((buy_quote, sell_quote, amount_in))
+    }
+
+    fn swap_request(&self, quote: &JupiterQuote, user: &Pubkey) -> JupiterSwapRequest {
+        JupiterSwapRequest {
+            quote_response: JupiterQuoteResponse::from(quote),
+            user_public_key: user.to_string(),
+            wrap_and_unwrap_sol: Some(true),
+            dynamic_compute_unit_limit: Some(self.config.jupiter.dynamic_compute_unit_limit),
+            prioritization_fee_lamports: None,
+            as_legacy_transaction: Some(false),
+            use_shared_accounts: Some(self.config.jupiter.use_shared_accounts),
+            fee_account: None,
+            tracking_account: None,
+            compute_unit_price_micro_lamports: None,
+        }
+    }
+
+    // Unsigned Jupiter swap transactions for both legs, the expected profit in
+    // quote-mint base units at the sell leg's quoted output, and the block height
+    // after which the first leg's blockhash (and so the bundle) has expired
+    async fn build_jupiter_legs(
+        &self,
+        user: &Pubkey,
+        request: &TradeRequest,
+        opportunity: &ArbitrageOpportunity,
+    ) -> Result<(Vec<VersionedTransaction>, u64, u64)> {
+        let jupiter_client = self.jupiter_client.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Jupiter client not available"))?;
+        let (buy_quote, sell_quote, amount_in) = self.quote_legs(request, opportunity).await?;
+
+        let mut legs = Vec::with_capacity(2);
+        let mut last_valid_block_height = u64::MAX;
+        for quote in [&buy_quote, &sell_quote] {
+            let swap = jupiter_client.get_swap_transaction(self.swap_request(quote, user)).await?;
+            let bytes = STANDARD.decode(&swap.swap_transaction)?;
+            legs.push(bincode::deserialize::<VersionedTransaction>(&bytes)?);
+            last_valid_block_height = last_valid_block_height.min(swap.last_valid_block_height);
+        }
+
+        let expected_profit = sell_quote.out_amount.saturating_sub(amount_in);
+        Ok((legs, expected_profit, last_valid_block_height))
+    }
+
+    async fn quote_amount_to_lamports(&self, quote_mint: &str, amount: u64) -> Result<u64> {
+        if quote_mint == WSOL_MINT || amount == 0 {
+            return Ok(amount);
+        }
+        Ok((amount as f64 * self.lamports_per_quote_unit(quote_mint).await?) as u64)
+    }
+
+    // Jupiter USD prices, kept by the risk manager to book P&L with when a
+    // later fetch fails
+    async fn get_prices(&self, mints: &[String]) -> Result<HashMap<String, f64>> {
+        let jupiter_client = self.jupiter_client.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Jupiter client not available"))?;
+        let prices = jupiter_client.get_price(mints).await?;
+        self.risk_manager.write().await.record_prices(&prices);
+        Ok(prices)
+    }
+
+    // USD value of `amount` whole units of the quote mint
+    async fn quote_usd_value(&self, quote_mint: &str, amount: f64) -> Result<f64> {
+        let prices = self.get_prices(&[quote_mint.to_string()]).await?;
+        let quote_usd = prices.get(quote_mint)
+            .ok_or_else(|| anyhow::anyhow!("No Jupiter price for {}", quote_mint))?;
+        Ok(amount * quote_usd)
+    }
+
+    // Books a result, profit in quote units less fees and tips in SOL, against
+    // the daily loss limit. Without a current price the last known one is used,
+    // and without any auto-trading halts, since the limit can't be enforced.
+    async fn record_pnl(&self, quote_mint: &str, profit: f64, gas_used: f64) {
+        if profit == 0.0 && gas_used == 0.0 {
+            return;
+        }
+        if let Err(e) = self.get_prices(&[quote_mint.to_string(), WSOL_MINT.to_string()]).await {
+            warn!("⚠️ Couldn't fetch prices for P&L, booking at the last known ones: {}", e);
+        }
+
+        let booked = self.risk_manager.write().await
+            .record_realized_pnl_in(&[(quote_mint, profit), (WSOL_MINT, -gas_used)]);
+        if let Err(mint) = booked {
+            error!("❌ No USD price for {}, couldn't book {:.6} {} less {:.6} SOL in fees",
+                   mint, profit, quote_mint, gas_used);
+            self.circuit_breaker.trip(TripReason::UnpricedPnl { mint }).await;
+        }
+    }
+
+    // Lamports per base unit of the quote mint, from Jupiter USD prices
+    async fn lamports_per_quote_unit(&self, quote_mint: &str) -> Result<f64> {
+        if quote_mint == WSOL_MINT {
+            return Ok(1.0);
+        }
+
+        let prices = self.get_prices(&[quote_mint.to_string(), WSOL_MINT.to_string()]).await?;
+        let (Some(quote_usd), Some(sol_usd)) = (prices.get(quote_mint), prices.get(WSOL_MINT)) else {
+            return Err(anyhow::anyhow!("No Jupiter price for {} or SOL", quote_mint));
+        };
+
+        let unit = 1.0 / 10f64.powi(token_decimals(quote_mint)? as i32);
+        Ok(unit * quote_usd / sol_usd * 1e9)
+    }
+
+    // Compiles each leg from Jupiter's swap instructions and sends it with a
+    // compute-unit price, waiting for the configured commitment in between. Not
+    // atomic like a bundle: if the sell leg fails, the bought tokens are booked as
+    // stranded and left for unwind_loop to sell.
+    async fn execute_rpc_trade(
+        &self,
+        request: &TradeRequest,
+        opportunity: &ArbitrageOpportunity,
+    ) -> Result<(TradeResponse, ExecutionOutcome)> {
+        let payer = parse_keypair(&request.private_key)?;
+        let (buy_quote, sell_quote, _) = self.quote_legs(request, opportunity).await?;
+        let (base_mint, quote_mint) = self.extract_token_mints(&opportunity.token_pair)?;
+
+        let leg_count = 2;
+        let mut confirmed = Vec::with_capacity(leg_count);
+        for quote in [&buy_quote, &sell_quote] {
+            match self.send_swap(&payer, quote, &quote_mint).await {
+                Ok(leg) => confirmed.push(leg),
+                Err(e) => {
+                    error!("❌ RPC send failed after {}/{} legs: {}", confirmed.len(), leg_count, e);
+                    if let Some(bought) = confirmed.first() {
+                        if let Err(e) = self.record_stranded(&payer.pubkey(), bought, &base_mint, &quote_mint).await {
+                            error!("❌ Couldn't record tokens left by {}: {}", bought.signature, e);
+                        }
+                    }
+                    let mut response = failed_trade_response(
+                        format!("RPC send failed after {}/{} legs: {}", confirmed.len(), leg_count, e),
+                        ExecutionPath::Rpc,
+                    );
+                    if let Some(first) = confirmed.first() {
+                        response.transaction_id = first.signature.to_string();
+                    }
+                    // Fees of legs that did land are still spent
+                    let fees: u64 = confirmed.iter().map(|leg| leg.change.fee_lamports).sum();
+                    response.gas_used = fees as f64 / 1e9;
+                    let outcome = if !confirmed.is_empty() {
+                        ExecutionOutcome::PartialFill
+                    } else if landed_and_failed(&e) {
+                        ExecutionOutcome::Reverted
+                    } else {
+                        ExecutionOutcome::NotLanded
+                    };
+                    return Ok((response, outcome));
+                }
+            }
+        }
+
+        let mut change = BalanceChange::default();
+        for leg in &confirmed {
+            change.merge(&leg.change);
+        }
+        let actual_profit = realized_profit(&change, &quote_mint, 0)?;
+        info!("💰 RPC trade {} confirmed: profit {:.6}, fees {} lamports",
+              confirmed[0].signature, actual_profit, change.fee_lamports);
+
+        Ok((TradeResponse {
+            transaction_id: confirmed[0].signature.to_string(),
             success: true,
             error_message: "".to_string(),
-            actual_profit: opportunity.estimated_profit,
-            gas_used: opportunity.gas_cost,
+            actual_profit,
+            gas_used: change.fee_lamports as f64 / 1e9,
             execution_time: 0,
             bundle_id: "".to_string(),
-        })
+            landed_slot: 0,
+            bundle_status: "".to_string(),
+            execution_path: ExecutionPath::Rpc.to_string(),
+        }, ExecutionOutcome::Filled))
+    }
+
+    // Builds a Jupiter swap right before sending it, so it gets a fresh
+    // blockhash and sees token accounts created by an earlier leg, then waits
+    // for it to confirm. `balance_mint` is the token whose change is read back.
+    async fn send_swap(&self, payer: &Keypair, quote: &JupiterQuote, balance_mint: &str) -> Result<ConfirmedTransaction> {
+        let jupiter_client = self.jupiter_client.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Jupiter client not available"))?;
+        let owner = payer.pubkey().to_string();
+        let route = route_key(&quote.route_plan);
+
+        let swap = jupiter_client.get_swap_instructions(self.swap_request(quote, &payer.pubkey())).await?;
+        let max_unit_price = self.risk_manager.read().await.settings().max_gas_price;
+        let mut prepared = self.transaction_builder.build_swap_transaction(payer, &swap, &route, max_unit_price).await?;
+
+        let mut resigns = 0;
+        let confirmed = loop {
+            match self.confirmation_tracker.send_and_track(&prepared, &owner, balance_mint).await {
+                Err(e) if is_blockhash_expired(&e) && resigns < self.config.execution.max_resign_attempts => {
+                    resigns += 1;
+                    warn!("🔁 Re-signing expired transaction ({}/{})",
+                          resigns, self.config.execution.max_resign_attempts);
+                    prepared = self.transaction_builder.resign(payer, &prepared).await?;
+                }
+                Err(e) if is_compute_budget_exceeded(&e) => {
+                    self.compute_budget.record_failure(&route).await;
+                    return Err(e);
+                }
+                // It may still land, and a buy that lands unseen leaves tokens
+                // nothing tracks. If it does, the trade carries on from there.
+                Err(e) if is_confirmation_timeout(&e) => {
+                    match self.confirmation_tracker.settle(&prepared, &owner, balance_mint).await {
+                        Ok(Some(confirmed)) => break confirmed,
+                        Ok(None) => return Err(e),
+                        Err(settle_error) => {
+                            if is_compute_budget_exceeded(&settle_error) {
+                                self.compute_budget.record_failure(&route).await;
+                            }
+                            return Err(settle_error);
+                        }
+                    }
+                }
+                result => break result?,
+            }
+        };
+        self.compute_budget.record(&route, confirmed.change.compute_units_consumed).await;
+        Ok(confirmed)
+    }
+
+    // Books what a buy leg bought and paid when its sell leg didn't land
+    async fn record_stranded(
+        &self,
+        owner: &Pubkey,
+        bought: &ConfirmedTransaction,
+        base_mint: &str,
+        quote_mint: &str,
+    ) -> Result<()> {
+        let rpc = self.rpc_pool.client().await;
+        let base_change = fetch_balance_change(&rpc, &bought.signature, &owner.to_string(), base_mint).await?;
+        let amount = quote_delta(&base_change, base_mint, 0).max(0) as u64;
+        let cost = (-quote_delta(&bought.change, quote_mint, 0)).max(0) as u64;
+        if amount == 0 {
+            return Ok(());
+        }
+        let decimals = self.token_screener.profiles(&[Pubkey::from_str(base_mint)?]).await?[0].decimals;
+
+        self.stranded_inventory.add(StrandedPosition {
+            mint: base_mint.to_string(),
+            quote_mint: quote_mint.to_string(),
+            decimals,
+            amount,
+            cost,
+            stranded_at: Utc::now(),
+        }).await;
+        self.sync_stranded_mints().await;
+        Ok(())
+    }
+
+    async fn sync_stranded_mints(&self) {
+        let mints = self.stranded_inventory.mints().await;
+        self.risk_manager.write().await.set_stranded_mints(mints);
+    }
+
+    // Base units of the position's mint the wallet holds, from the portfolio.
+    // For SOL, what's above the configured minimum balance. A mint missing from
+    // the portfolio is an error rather than zero, so the position isn't dropped.
+    async fn held_amount(&self, position: &StrandedPosition) -> Result<u64> {
+        let portfolio = self.portfolio_manager.get_portfolio().await?;
+        let balance = portfolio.balances
+            .iter()
+            .find(|balance| balance.token_mint == position.mint)
+            .ok_or_else(|| anyhow::anyhow!("{} isn't in the portfolio", position.mint))?;
+        let held = (balance.amount * 10f64.powi(position.decimals as i32)).round() as u64;
+
+        if position.mint == WSOL_MINT {
+            let reserve = (self.config.wallet.min_sol_balance * 1e9) as u64;
+            return Ok(held.saturating_sub(reserve));
+        }
+        Ok(held)
+    }
+
+    // Sells stranded tokens back into the quote mint once they're worth their
+    // cost again, when the loss crosses the stop loss, or after max_hold_ms
+    async fn unwind_loop(&self) {
+        let mut interval = tokio::time::interval(Duration::from_millis(self.config.unwind.check_interval_ms));
+        loop {
+            interval.tick().await;
+            if !*self.is_running.read().await {
+                break;
+            }
+
+            for position in self.stranded_inventory.positions().await {
+                if let Err(e) = self.check_stranded(&position).await {
+                    warn!("⚠️ Couldn't check stranded {}: {}", position.mint, e);
+                }
+            }
+        }
+    }
+
+    async fn check_stranded(&self, position: &StrandedPosition) -> Result<()> {
+        let payer = parse_keypair(&self.config.wallet.private_key)?;
+
+        // Anything sold or moved out of the wallet since isn't ours to unwind
+        let held = self.held_amount(position).await?;
+        if held < position.amount {
+            info!("🧳 Wallet holds {} of {} stranded {} units", held, position.amount, position.mint);
+            self.stranded_inventory.reduce(&position.mint, &position.quote_mint, position.amount - held).await;
+            self.sync_stranded_mints().await;
+            if held == 0 {
+                return Ok(());
+            }
+        }
+        let amount = position.amount.min(held);
+        let cost = (position.cost as u128 * amount as u128 / position.amount as u128) as u64;
+        let position = StrandedPosition { amount, cost, ..position.clone() };
+
+        let jupiter_client = self.jupiter_client.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Jupiter client not available"))?;
+        let quote = jupiter_client.get_quote(JupiterQuoteRequest {
+            input_mint: position.mint.clone(),
+            output_mint: position.quote_mint.clone(),
+            amount,
+            slippage_bps: self.config.unwind.slippage_bps,
+            swap_mode: Some(SwapMode::ExactIn),
+            dexes: None,
+            exclude_dexes: Some(self.config.jupiter.excluded_dexes.clone()),
+            platform_fee_bps: None,
+            max_accounts: Some(self.config.jupiter.max_route_accounts),
+        }).await?;
+
+        let settings = self.risk_manager.read().await.settings().clone();
+        let stop_loss = settings.enable_stop_loss.then_some(settings.stop_loss_percentage);
+        let max_hold = Duration::from_millis(self.config.unwind.max_hold_ms);
+        // Marked at the worst fill we'd accept, so a stop loss isn't missed by slippage
+        let value = quote.min_out_amount();
+        gauge!("stranded_loss_pct", "mint" => position.mint.clone()).set(position.loss_pct(value));
+        let Some(reason) = unwind_decision(&position, value, stop_loss, max_hold) else {
+            debug!("🧳 Holding {} of {}, {:.2}% below cost", amount, position.mint, position.loss_pct(value));
+            return Ok(());
+        };
+
+        info!("🧯 Unwinding {} of {} into {}: {}", amount, position.mint, position.quote_mint, reason);
+        let sold = self.send_swap(&payer, &quote, &position.quote_mint).await?;
+        self.stranded_inventory.reduce(&position.mint, &position.quote_mint, amount).await;
+        self.sync_stranded_mints().await;
+        counter!("stranded_unwinds_total", "reason" => reason.kind()).increment(1);
+
+        let decimals = 10f64.powi(token_decimals(&position.quote_mint)? as i32);
+        let proceeds = quote_delta(&sold.change, &position.quote_mint, 0);
+        let pnl = (proceeds - position.cost as i128) as f64 / decimals;
+        info!("🧯 Unwind {} confirmed: {:.6} against cost", sold.signature, pnl);
+        let fees = sold.change.fee_lamports as f64 / 1e9;
+        self.record_pnl(&position.quote_mint, pnl, fees).await;
+        Ok(())
+    }
+
+    // Realized profit in quote units, from the wallet's balance changes across
+    // `signatures`, along with the summed fees
+    async fn realized_change(
+        &self,
+        signatures: &[Signature],
+        owner: &Pubkey,
+        quote_mint: &str,
+        tip_lamports: u64,
+    ) -> Result<(f64, BalanceChange)> {
+        let owner = owner.to_string();
+        let rpc = self.rpc_pool.client().await;
+        let mut change = BalanceChange::default();
+        for signature in signatures {
+            let tx_change = fetch_balance_change(&rpc, signature, &owner, quote_mint).await?;
+            change.merge(&tx_change);
+        }
+
+        Ok((realized_profit(&change, quote_mint, tip_lamports)?, change))
     }
 
+    // For ExactOut, `amount` is the output we need back, e.g. to repay the opening leg
     async fn get_jupiter_quote(
         &self,
         input_mint: &str,
         output_mint: &str,
         amount: u64,
+        swap_mode: SwapMode,
     ) -> Result<JupiterQuote> {
         if let Some(jupiter_client) = &self.jupiter_client {
-            use crate::jupiter_client::JupiterQuoteRequest;
-            
             let request = JupiterQuoteRequest {
                 input_mint: input_mint.to_string(),
                 output_mint: output_mint.to_string(),
                 amount,
                 slippage_bps: self.config.jupiter.default_slippage_bps,
-                swap_mode: Some("ExactIn".to_string()),
+                swap_mode: Some(swap_mode),
                 dexes: Some(self.config.jupiter.preferred_dexes.clone()),
                 exclude_dexes: Some(self.config.jupiter.excluded_dexes.clone()),
                 platform_fee_bps: None,
-                max_accounts: Some(64),
+                max_accounts: Some(self.config.jupiter.max_route_accounts),
             };
 
-            jupiter_client.get_quote(request).await
+            // Retry transient failures (rate limits, network errors, 5xx) with a linear backoff
+            let mut attempt = 0;
+            loop {
+                let result = jupiter_client.get_quote(request.clone()).await;
+                let retry_after = match &result {
+                    Err(e) => match e.downcast_ref::<JupiterError>() {
+                        Some(err) if err.action() == JupiterErrorAction::Retry => match err {
+                            JupiterError::RateLimited { retry_after_ms: Some(ms) } => Some(*ms),
+                            _ => Some(250 * (attempt as u64 + 1)),
+                        },
+                        _ => None,
+                    },
+                    Ok(_) => None,
+                };
+
+                attempt += 1;
+                match retry_after {
+                    Some(ms) if attempt < self.config.jupiter.retry_attempts => {
+                        debug!("🔁 Retrying Jupiter quote in {}ms (attempt {})", ms, attempt + 1);
+                        tokio::time::sleep(Duration::from_millis(ms)).await;
+                    }
+                    _ => return result,
+                }
+            }
         } else {
             Err(anyhow::anyhow!("Jupiter client not available"))
         }
     }
 
+    // Drop quotes routed through excluded AMMs or with more hops than configured.
+    // Account counts are already capped by the quote's max_accounts.
+    async fn check_route(&self, token_pair: &str, quote: JupiterQuote) -> Option<JupiterQuote> {
+        let prices = self.fee_mint_prices(&quote).await.unwrap_or_else(|e| {
+            debug!("💲 Could not price route fees for {}: {}", token_pair, e);
+            HashMap::new()
+        });
+        let analysis = match analyze_route(&quote, &prices) {
+            Ok(analysis) => analysis,
+            Err(e) => {
+                warn!("⚠️ Could not analyze Jupiter route for {}: {}", token_pair, e);
+                return None;
+            }
+        };
+
+        if let Some(leg) = analysis.find_amm(&self.config.jupiter.excluded_dexes) {
+            debug!("🚫 Rejecting {} route through excluded AMM {}", token_pair, leg.label);
+            return None;
+        }
+        if analysis.hops > self.config.jupiter.max_route_hops {
+            debug!("🚫 Rejecting {} route with {} hops", token_pair, analysis.hops);
+            return None;
+        }
+
+        if analysis.unpriced_legs == 0 {
+            histogram!("jupiter_route_fee_usd").record(analysis.total_fee_usd);
+        }
+        debug!("🛣️ {} route: {} hops, fees ${:.4}{}", token_pair, analysis.hops, analysis.total_fee_usd,
+               if analysis.unpriced_legs > 0 { " (partial)" } else { "" });
+        Some(quote)
+    }
+
+    // USD prices of the mints a route charges fees in. Mints with unknown
+    // decimals are left out and show up as unpriced legs.
+    async fn fee_mint_prices(&self, quote: &JupiterQuote) -> Result<HashMap<String, MintPrice>> {
+        let mut fee_mints: Vec<String> = quote.route_plan.iter()
+            .map(|plan| plan.swap_info.fee_mint.clone())
+            .collect();
+        fee_mints.sort();
+        fee_mints.dedup();
+
+        let prices = self.get_prices(&fee_mints).await?;
+        Ok(prices
+            .into_iter()
+            .filter_map(|(mint, price_usd)| {
+                let decimals = token_decimals(&mint).ok()?;
+                Some((mint, MintPrice { price_usd, decimals }))
+            })
+            .collect())
+    }
+
+    async fn is_pair_cooling_down(&self, token_pair: &str) -> bool {
+        let mut cooldowns = self.pair_cooldowns.write().await;
+        match cooldowns.get(token_pair) {
+            Some(until) if *until > Instant::now() => true,
+            Some(_) => {
+                cooldowns.remove(token_pair);
+                false
+            }
+            None => false,
+        }
+    }
+
+    // Decide what a failed Jupiter call means for the engine. Returns an error only
+    // when trading must halt.
+    async fn handle_jupiter_error(&self, token_pair: &str, err: &anyhow::Error) -> Result<()> {
+        let Some(jupiter_error) = err.downcast_ref::<JupiterError>() else {
+            return Ok(());
+        };
+
+        match jupiter_error.action() {
+            JupiterErrorAction::Retry | JupiterErrorAction::Skip => {}
+            JupiterErrorAction::CooldownPair => {
+                let cooldown = Duration::from_millis(self.config.jupiter.pair_cooldown_ms);
+                info!("⏸️ Cooling down {} for {:?}: {}", token_pair, cooldown, jupiter_error);
+                self.pair_cooldowns
+                    .write()
+                    .await
+                    .insert(token_pair.to_string(), Instant::now() + cooldown);
+            }
+            JupiterErrorAction::Halt => {
+                error!("🛑 Halting arbitrage engine: {}", jupiter_error);
+                *self.is_running.write().await = false;
+                return Err(ArbitrageError::from(jupiter_error.clone()).into());
+            }
+        }
+
+        Ok(())
+    }
+
     fn extract_token_mints(&self, token_pair: &str) -> Result<(String, String)> {
         // Simplified token mint extraction
         // In a real implementation, you'd have a mapping from token pairs to mint addresses
@@ -476,12 +1353,14 @@ impl ArbitrageEngine {
         &self,
         opportunity: &EnhancedArbitrageOpportunity,
         amount: u64,
+        swap_mode: SwapMode,
     ) -> Result<SwapResponse> {
         if let Some(jupiter_client) = &self.jupiter_client {
             let swap_request = SwapRequest {
                 input_mint: opportunity.input_mint.clone(),
                 output_mint: opportunity.output_mint.clone(),
                 amount,
+                swap_mode,
                 user_public_key: self.config.wallet.public_key.clone(),
                 slippage: self.config.jupiter.default_slippage_bps as f64 / 100.0,
                 priority_fee: self.config.jupiter.prioritization_fee_lamports,
@@ -505,7 +1384,96 @@ impl ArbitrageEngine {
             jito_client: self.jito_client.clone(),
             jupiter_client: self.jupiter_client.clone(),
             monitoring: self.monitoring.clone(),
+            rpc_pool: self.rpc_pool.clone(),
+            blockhash_manager: self.blockhash_manager.clone(),
+            lookup_table_manager: self.lookup_table_manager.clone(),
+            compute_budget: self.compute_budget.clone(),
+            transaction_builder: self.transaction_builder.clone(),
+            confirmation_tracker: self.confirmation_tracker.clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
+            token_screener: self.token_screener.clone(),
+            stranded_inventory: self.stranded_inventory.clone(),
             is_running: self.is_running.clone(),
+            pair_cooldowns: self.pair_cooldowns.clone(),
+            opportunities: self.opportunities.clone(),
         }
     }
 }
+
+fn failed_trade_response(error_message: String, path: ExecutionPath) -> TradeResponse {
+    TradeResponse {
+        transaction_id: "".to_string(),
+        success: false,
+        error_message,
+        actual_profit: 0.0,
+        gas_used: 0.0,
+        execution_time: 0,
+        bundle_id: "".to_string(),
+        landed_slot: 0,
+        bundle_status: "".to_string(),
+        execution_path: path.to_string(),
+    }
+}
+
+// Profit in quote units. Wrapped SOL is unwrapped back into lamports at the end
+// of each swap, and fees and tip are reported separately, so they're added back.
+fn realized_profit(change: &BalanceChange, quote_mint: &str, tip_lamports: u64) -> Result<f64> {
+    Ok(quote_delta(change, quote_mint, tip_lamports) as f64 / 10f64.powi(token_decimals(quote_mint)? as i32))
+}
+
+// Change in `mint` base units. SOL moves as lamports, not counting fees and tips.
+fn quote_delta(change: &BalanceChange, mint: &str, tip_lamports: u64) -> i128 {
+    let mut delta = change.token_delta;
+    if mint == WSOL_MINT {
+        delta += (change.lamport_delta + change.fee_lamports as i64 + tip_lamports as i64) as i128;
+    }
+    delta
+}
+
+fn is_simulation_rejection(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<ArbitrageError>(), Some(ArbitrageError::SimulationRejected(_)))
+}
+
+fn is_confirmation_timeout(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<ArbitrageError>(), Some(ArbitrageError::ConfirmationTimeout(_)))
+}
+
+fn is_blockhash_expired(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<ArbitrageError>(), Some(ArbitrageError::BlockhashExpired(_)))
+}
+
+fn check_price_impact(quote: &JupiterQuote, max_pct: f64) -> Result<()> {
+    if quote.price_impact_pct > max_pct {
+        debug!("📉 Price impact {:.2}% of {} -> {} exceeds {:.2}%",
+               quote.price_impact_pct, quote.input_mint, quote.output_mint, max_pct);
+        return Err(ArbitrageError::PriceImpactTooHigh.into());
+    }
+    Ok(())
+}
+
+// The transaction made it into a block and its instructions failed
+fn landed_and_failed(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<ArbitrageError>(),
+        Some(ArbitrageError::TransactionFailed(_) | ArbitrageError::ComputeBudgetExceeded(_))
+    )
+}
+
+fn is_compute_budget_exceeded(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<ArbitrageError>(), Some(ArbitrageError::ComputeBudgetExceeded(_)))
+}
+
+fn record_execution(path: ExecutionPath, outcome: &'static str) {
+    counter!("trade_executions_total", "path" => path.as_str(), "outcome" => outcome).increment(1);
+}
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+fn token_decimals(mint: &str) -> Result<u8> {
+    match mint {
+        "So11111111111111111111111111111111111111112" => Ok(9),
+        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v" => Ok(6),
+        "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB" => Ok(6),
+        _ => Err(anyhow::anyhow!("Unknown decimals for mint: {}", mint)),
+    }
+}
@@ -0,0 +1,136 @@
+use crate::rpc_pool::RpcPool;
+use anyhow::Result;
+use solana_client::client_error::ClientError;
+use solana_sdk::{commitment_config::CommitmentConfig, hash::Hash};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, RwLock};
+use tracing::{debug, error, info, warn};
+
+#[derive(Debug, Clone, Copy)]
+pub struct RecentBlockhash {
+    pub blockhash: Hash,
+    // Transactions using this blockhash can't land after this block height
+    pub last_valid_block_height: u64,
+    pub fetched_at: Instant,
+}
+
+// Checks a transaction's last valid block height against the block height the
+// manager last saw, without an RPC round trip
+#[derive(Debug, Clone)]
+pub struct ExpiryWatch {
+    last_valid_block_height: u64,
+    block_height: watch::Receiver<u64>,
+}
+
+impl ExpiryWatch {
+    pub fn is_expired(&self) -> bool {
+        *self.block_height.borrow() > self.last_valid_block_height
+    }
+}
+
+// Shared recent blockhash, refreshed in the background so transaction builders
+// don't fetch one per trade
+pub struct BlockhashManager {
+    rpc_pool: Arc<RpcPool>,
+    commitment: CommitmentConfig,
+    refresh_interval: Duration,
+    current: RwLock<Option<RecentBlockhash>>,
+    block_height: watch::Sender<u64>,
+    is_running: RwLock<bool>,
+}
+
+impl BlockhashManager {
+    pub fn new(rpc_pool: Arc<RpcPool>, commitment: CommitmentConfig, refresh_interval: Duration) -> Self {
+        Self {
+            rpc_pool,
+            commitment,
+            refresh_interval,
+            current: RwLock::new(None),
+            block_height: watch::channel(0).0,
+            is_running: RwLock::new(false),
+        }
+    }
+
+    pub async fn start(self: &Arc<Self>) {
+        let mut running = self.is_running.write().await;
+        if *running {
+            return;
+        }
+        *running = true;
+        drop(running);
+
+        info!("🧱 Starting blockhash refresh every {:?}", self.refresh_interval);
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(manager.refresh_interval);
+            loop {
+                interval.tick().await;
+                if !*manager.is_running.read().await {
+                    break;
+                }
+                if let Err(e) = manager.refresh().await {
+                    warn!("⚠️ Blockhash refresh failed: {}", e);
+                }
+            }
+        });
+    }
+
+    pub async fn stop(&self) {
+        *self.is_running.write().await = false;
+    }
+
+    pub async fn refresh(&self) -> Result<RecentBlockhash> {
+        // Both from the same node, so the expiry check compares like with like
+        let commitment = self.commitment;
+        let (blockhash, last_valid_block_height, block_height) = self
+            .rpc_pool
+            .read(|rpc| async move {
+                let (blockhash, last_valid_block_height) = rpc.get_latest_blockhash_with_commitment(commitment).await?;
+                let block_height = rpc.get_block_height_with_commitment(commitment).await?;
+                Ok::<_, ClientError>((blockhash, last_valid_block_height, block_height))
+            })
+            .await?;
+
+        let recent = RecentBlockhash {
+            blockhash,
+            last_valid_block_height,
+            fetched_at: Instant::now(),
+        };
+        *self.current.write().await = Some(recent);
+        self.block_height.send_replace(block_height);
+
+        debug!("🧱 Blockhash {} valid through block {} (now {})",
+               blockhash, last_valid_block_height, block_height);
+        Ok(recent)
+    }
+
+    // The cached blockhash, or a fresh one if the background refresh has fallen
+    // behind by more than a few intervals
+    pub async fn get(&self) -> Result<RecentBlockhash> {
+        if let Some(recent) = *self.current.read().await {
+            if recent.fetched_at.elapsed() < self.refresh_interval * 3 {
+                return Ok(recent);
+            }
+        }
+
+        self.refresh().await.inspect_err(|e| error!("❌ Failed to fetch blockhash: {}", e))
+    }
+
+    // Authoritative check at `commitment`. The watch follows the manager's own
+    // commitment, which can run ahead of the one a signature status is read at.
+    pub async fn is_expired_at(&self, last_valid_block_height: u64, commitment: CommitmentConfig) -> Result<bool> {
+        let block_height = self
+            .rpc_pool
+            .read(|rpc| async move { rpc.get_block_height_with_commitment(commitment).await })
+            .await?;
+        Ok(block_height > last_valid_block_height)
+    }
+
+    pub fn expiry_watch(&self, last_valid_block_height: u64) -> ExpiryWatch {
+        ExpiryWatch {
+            last_valid_block_height,
+            block_height: self.block_height.subscribe(),
+        }
+    }
+}
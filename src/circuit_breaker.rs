@@ -0,0 +1,198 @@
+use crate::config::CircuitBreakerConfig;
+use chrono::{DateTime, Utc};
+use metrics::{counter, gauge};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum TripReason {
+    ConsecutiveFailures { count: u32 },
+    // Realized profit fell short of the estimate by this share of the notional
+    SlippageExceeded { realized_pct: f64, limit_pct: f64 },
+    LowBalance { balance_sol: f64, min_sol: f64 },
+    // Slots the best RPC endpoint is behind the cluster tip
+    RpcSlotLag { lag: u64, limit: u64 },
+    // Every RPC endpoint is unreachable or ejected for lagging
+    RpcUnavailable,
+    PriceDivergence { token_pair: String, spread_pct: f64, limit_pct: f64 },
+    // A trade's result couldn't be booked against the daily loss limit
+    UnpricedPnl { mint: String },
+}
+
+impl TripReason {
+    // Metric label for the trip
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TripReason::ConsecutiveFailures { .. } => "consecutive_failures",
+            TripReason::SlippageExceeded { .. } => "slippage",
+            TripReason::LowBalance { .. } => "low_balance",
+            TripReason::RpcSlotLag { .. } => "rpc_slot_lag",
+            TripReason::RpcUnavailable => "rpc_unavailable",
+            TripReason::PriceDivergence { .. } => "price_divergence",
+            TripReason::UnpricedPnl { .. } => "unpriced_pnl",
+        }
+    }
+}
+
+impl std::fmt::Display for TripReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TripReason::ConsecutiveFailures { count } => write!(f, "{} consecutive failed trades", count),
+            TripReason::SlippageExceeded { realized_pct, limit_pct } => {
+                write!(f, "realized slippage {:.2}% above {:.2}%", realized_pct, limit_pct)
+            }
+            TripReason::LowBalance { balance_sol, min_sol } => {
+                write!(f, "wallet balance {:.4} SOL below {:.4} SOL", balance_sol, min_sol)
+            }
+            TripReason::RpcSlotLag { lag, limit } => write!(f, "RPC {} slots behind, limit {}", lag, limit),
+            TripReason::RpcUnavailable => write!(f, "no RPC endpoint reachable and in sync"),
+            TripReason::PriceDivergence { token_pair, spread_pct, limit_pct } => {
+                write!(f, "{} prices {:.2}% apart, limit {:.2}%", token_pair, spread_pct, limit_pct)
+            }
+            TripReason::UnpricedPnl { mint } => write!(f, "no USD price to book P&L in {}", mint),
+        }
+    }
+}
+
+// What a trade did on chain, as far as the breaker is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionOutcome {
+    Filled,
+    // Dropped, expired or rejected before sending. Nothing went wrong on chain.
+    NotLanded,
+    // Landed and failed, fees paid for nothing
+    Reverted,
+    // Some legs landed, leaving inventory behind
+    PartialFill,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trip {
+    pub reason: TripReason,
+    pub tripped_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    trip: Option<Trip>,
+    consecutive_failures: u32,
+}
+
+// Halts auto-trading when trades, the wallet, RPC or price feeds look wrong.
+// Stays open until an operator resumes it.
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: RwLock<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        gauge!("circuit_breaker_tripped").set(0.0);
+        Self {
+            config,
+            state: RwLock::new(BreakerState::default()),
+        }
+    }
+
+    pub async fn trip_status(&self) -> Option<Trip> {
+        self.state.read().await.trip.clone()
+    }
+
+    pub async fn is_tripped(&self) -> bool {
+        self.state.read().await.trip.is_some()
+    }
+
+    // Keeps the first reason if already tripped, that's the one to investigate
+    pub async fn trip(&self, reason: TripReason) {
+        if !self.config.enabled {
+            return;
+        }
+        let mut state = self.state.write().await;
+        if state.trip.is_some() {
+            return;
+        }
+
+        error!("🚨 Circuit breaker tripped, auto-trading halted: {}", reason);
+        counter!("circuit_breaker_trips_total", "reason" => reason.kind()).increment(1);
+        gauge!("circuit_breaker_tripped").set(1.0);
+        state.trip = Some(Trip {
+            reason,
+            tripped_at: Utc::now(),
+        });
+    }
+
+    // Returns the trip that was cleared, if any
+    pub async fn resume(&self, source: &str) -> Option<Trip> {
+        let mut state = self.state.write().await;
+        let trip = state.trip.take()?;
+        state.consecutive_failures = 0;
+        gauge!("circuit_breaker_tripped").set(0.0);
+        info!("▶️ Circuit breaker reset via {} after: {}", source, trip.reason);
+        Some(trip)
+    }
+
+    // Only trades that went wrong on chain count as failures, ones that never
+    // landed leave the streak as it is. Realized slippage is only known for fills.
+    pub async fn record_trade(&self, outcome: ExecutionOutcome, realized_slippage_pct: Option<f64>) {
+        let failures = {
+            let mut state = self.state.write().await;
+            match outcome {
+                ExecutionOutcome::Filled => state.consecutive_failures = 0,
+                ExecutionOutcome::NotLanded => {}
+                ExecutionOutcome::Reverted | ExecutionOutcome::PartialFill => state.consecutive_failures += 1,
+            }
+            state.consecutive_failures
+        };
+
+        if failures >= self.config.max_consecutive_failures {
+            self.trip(TripReason::ConsecutiveFailures { count: failures }).await;
+        }
+        if let Some(realized_pct) = realized_slippage_pct {
+            if realized_pct > self.config.max_realized_slippage_pct {
+                self.trip(TripReason::SlippageExceeded {
+                    realized_pct,
+                    limit_pct: self.config.max_realized_slippage_pct,
+                })
+                .await;
+            }
+        }
+    }
+
+    pub async fn check_balance(&self, balance_sol: f64, min_sol: f64) {
+        if balance_sol < min_sol {
+            self.trip(TripReason::LowBalance { balance_sol, min_sol }).await;
+        }
+    }
+
+    // `lag` is None when no RPC endpoint is in rotation
+    pub async fn check_slot_lag(&self, lag: Option<u64>) {
+        let Some(lag) = lag else {
+            self.trip(TripReason::RpcUnavailable).await;
+            return;
+        };
+        if lag > self.config.max_slot_lag {
+            self.trip(TripReason::RpcSlotLag {
+                lag,
+                limit: self.config.max_slot_lag,
+            })
+            .await;
+        }
+    }
+
+    // Spreads this wide between DEX quotes are bad data, not arbitrage.
+    // Returns false if the prices shouldn't be traded on.
+    pub async fn check_price_spread(&self, token_pair: &str, spread_pct: f64) -> bool {
+        if !self.config.enabled || spread_pct <= self.config.max_price_divergence_pct {
+            return true;
+        }
+        self.trip(TripReason::PriceDivergence {
+            token_pair: token_pair.to_string(),
+            spread_pct,
+            limit_pct: self.config.max_price_divergence_pct,
+        })
+        .await;
+        false
+    }
+}
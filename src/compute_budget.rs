@@ -0,0 +1,132 @@
+use crate::config::ComputeBudgetConfig;
+use crate::rpc_pool::RpcPool;
+use crate::types::RoutePlan;
+use metrics::counter;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+// Runtime cap on a transaction's compute unit limit
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+// getRecentPrioritizationFees accepts at most this many accounts
+const MAX_FEE_ACCOUNTS: usize = 128;
+
+// Sizes compute unit limits from simulated or observed usage per route, and
+// prices compute units from recent prioritization fees
+pub struct ComputeBudgetPlanner {
+    rpc_pool: Arc<RpcPool>,
+    config: ComputeBudgetConfig,
+    // Used when no recent fees are available
+    fallback_unit_price: u64,
+    history: RwLock<HashMap<String, RouteHistory>>,
+}
+
+#[derive(Debug, Default)]
+struct RouteHistory {
+    // Most recent compute units consumed, oldest first
+    samples: VecDeque<u64>,
+    simulated_at: Option<Instant>,
+}
+
+impl ComputeBudgetPlanner {
+    pub fn new(rpc_pool: Arc<RpcPool>, config: ComputeBudgetConfig, fallback_unit_price: u64) -> Self {
+        Self {
+            rpc_pool,
+            config,
+            fallback_unit_price,
+            history: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn simulation_enabled(&self) -> bool {
+        self.config.simulate
+    }
+
+    pub fn limit_with_margin(&self, units_consumed: u64) -> u32 {
+        let limit = (units_consumed as f64 * (1.0 + self.config.unit_limit_margin)).ceil();
+        limit.min(MAX_COMPUTE_UNIT_LIMIT as f64) as u32
+    }
+
+    // A limit from history once the route has been seen often enough to skip
+    // simulating it, and was simulated within hot_route_ttl_ms. Sized from the
+    // heaviest recent run.
+    pub async fn known_limit(&self, route_key: &str) -> Option<u32> {
+        let history = self.history.read().await;
+        let route = history.get(route_key)?;
+        if route.samples.len() < self.config.hot_route_samples {
+            return None;
+        }
+        let ttl = Duration::from_millis(self.config.hot_route_ttl_ms);
+        if route.simulated_at.is_none_or(|at| at.elapsed() >= ttl) {
+            return None;
+        }
+        route.samples.iter().max().map(|units| self.limit_with_margin(*units))
+    }
+
+    pub async fn record(&self, route_key: &str, units_consumed: u64) {
+        if units_consumed == 0 {
+            return;
+        }
+        let mut history = self.history.write().await;
+        let samples = &mut history.entry(route_key.to_string()).or_default().samples;
+        samples.push_back(units_consumed);
+        while samples.len() > self.config.history_size.max(1) {
+            samples.pop_front();
+        }
+    }
+
+    pub async fn record_simulation(&self, route_key: &str, units_consumed: u64) {
+        self.record(route_key, units_consumed).await;
+        if let Some(route) = self.history.write().await.get_mut(route_key) {
+            route.simulated_at = Some(Instant::now());
+        }
+    }
+
+    // A transaction on the route ran out of compute units, so its history
+    // undersized the limit. Drops it so the next run is simulated again.
+    pub async fn record_failure(&self, route_key: &str) {
+        counter!("compute_budget_exceeded_total").increment(1);
+        warn!("⛽ Route {} ran out of compute units, re-simulating it", route_key);
+        self.history.write().await.remove(route_key);
+    }
+
+    // The configured percentile of recent prioritization fees paid to write the
+    // given accounts, capped at max_unit_price_micro_lamports
+    pub async fn unit_price(&self, writable_accounts: &[Pubkey]) -> u64 {
+        let accounts = &writable_accounts[..writable_accounts.len().min(MAX_FEE_ACCOUNTS)];
+        let fees = match self
+            .rpc_pool
+            .read(|rpc| async move { rpc.get_recent_prioritization_fees(accounts).await })
+            .await
+        {
+            Ok(fees) => fees,
+            Err(e) => {
+                warn!("⚠️ Failed to fetch prioritization fees, using {}: {}", self.fallback_unit_price, e);
+                return self.fallback_unit_price;
+            }
+        };
+
+        let mut prices: Vec<u64> = fees.iter().map(|fee| fee.prioritization_fee).collect();
+        if prices.is_empty() {
+            return self.fallback_unit_price;
+        }
+        prices.sort_unstable();
+        let percentile = self.config.priority_fee_percentile.min(100) as usize;
+        let index = ((prices.len() - 1) * percentile) / 100;
+        let price = prices[index].min(self.config.max_unit_price_micro_lamports);
+        debug!("💸 CU price {} micro-lamports (p{} of {} slots)", price, percentile, prices.len());
+        price
+    }
+}
+
+// Identifies a route by the pools it swaps through, in order
+pub fn route_key(route_plan: &[RoutePlan]) -> String {
+    route_plan
+        .iter()
+        .map(|step| step.swap_info.amm_key.as_str())
+        .collect::<Vec<_>>()
+        .join(">")
+}
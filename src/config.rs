@@ -13,14 +13,40 @@ pub struct Config {
     pub risk_settings: RiskSettings,
     pub monitoring: MonitoringConfig,
     pub trading: TradingConfig,
+    #[serde(default)]
+    pub execution: ExecutionConfig,
+    #[serde(default)]
+    pub lookup_tables: LookupTableConfig,
+    #[serde(default)]
+    pub compute_budget: ComputeBudgetConfig,
+    #[serde(default)]
+    pub admin: AdminConfig,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    #[serde(default)]
+    pub token_screening: TokenScreeningConfig,
+    #[serde(default)]
+    pub exposure_limits: ExposureLimitsConfig,
+    #[serde(default)]
+    pub unwind: UnwindConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RpcConfig {
     pub primary: String,
+    // Websocket endpoint for signatureSubscribe. Empty falls back to polling.
+    #[serde(default)]
+    pub ws_url: String,
     pub secondary: Vec<String>,
     pub timeout_ms: u64,
+    // Endpoints tried per read before giving up
     pub retry_attempts: u32,
+    // 0 uses the defaults of 5 seconds and 50 slots
+    #[serde(default)]
+    pub health_check_interval_ms: u64,
+    // Endpoints further behind the cluster tip are taken out of rotation
+    #[serde(default)]
+    pub max_slot_lag: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,22 +81,185 @@ pub struct WalletConfig {
 pub struct JitoConfig {
     pub enabled: bool,
     pub tip_account: String,
+    // Fallback rotation set used when getTipAccounts is unavailable
+    #[serde(default)]
+    pub tip_accounts: Vec<String>,
     pub bundle_endpoint: String,
+    // Regional block engines. Bundles go to the fanout_regions fastest of these
+    // (0 = all of them), with bundle_endpoint appended if not already listed.
+    #[serde(default)]
+    pub block_engines: Vec<BlockEngineConfig>,
+    #[serde(default)]
+    pub fanout_regions: usize,
     pub max_tip_lamports: u64,
     pub min_tip_lamports: u64,
+    // How long to poll bundle statuses before treating a bundle as dropped
+    #[serde(default = "default_bundle_timeout_ms")]
+    pub bundle_timeout_ms: u64,
+    #[serde(default = "default_status_poll_interval_ms")]
+    pub status_poll_interval_ms: u64,
+    // Dynamic tips: a share of expected profit, raised to a recent landed-tip percentile
+    #[serde(default = "default_tip_floor_url")]
+    pub tip_floor_url: String,
+    #[serde(default = "default_tip_floor_percentile")]
+    pub tip_floor_percentile: u8,
+    #[serde(default = "default_tip_floor_refresh_ms")]
+    pub tip_floor_refresh_ms: u64,
+    #[serde(default = "default_tip_profit_fraction")]
+    pub tip_profit_fraction: f64,
+    // RPC implementing simulateBundle (Jito-Solana). Empty skips simulation.
+    #[serde(default)]
+    pub simulation_rpc_url: String,
+    // Simulated net gain, after fees and tip, a bundle needs before it is sent
+    #[serde(default)]
+    pub min_simulated_net_lamports: i64,
+}
+
+fn default_bundle_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_status_poll_interval_ms() -> u64 {
+    1_000
+}
+
+fn default_tip_floor_url() -> String {
+    "https://bundles.jito.wtf/api/v1/bundles/tip_floor".to_string()
+}
+
+fn default_tip_floor_percentile() -> u8 {
+    50
+}
+
+fn default_tip_floor_refresh_ms() -> u64 {
+    10_000
+}
+
+fn default_tip_profit_fraction() -> f64 {
+    0.5
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockEngineConfig {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RiskSettings {
     pub max_position_size: f64,
+    // USD
     pub max_daily_loss: f64,
+    // Percentages
     pub max_slippage: f64,
     pub min_profit_threshold: f64,
     pub max_trades_per_hour: u32,
     pub enable_stop_loss: bool,
     pub stop_loss_percentage: f64,
+    // Highest compute unit price paid, in micro-lamports
     pub max_gas_price: u64,
+    // Pools with less are left out of scans
     pub min_liquidity: f64,
+    // Off leaves auto-trading scanning only, every trade runs through Jupiter
+    pub use_jupiter_for_execution: bool,
+    // Slippage for Jupiter quotes, capped by max_slippage
+    pub jupiter_slippage_bps: u16,
+    // Per leg, from Jupiter's quote
+    pub max_price_impact_pct: f64,
+}
+
+impl RiskSettings {
+    pub fn validate(&self) -> Result<()> {
+        let non_negative = [
+            ("max_position_size", self.max_position_size),
+            ("max_daily_loss", self.max_daily_loss),
+            ("min_profit_threshold", self.min_profit_threshold),
+            ("min_liquidity", self.min_liquidity),
+        ];
+        for (name, value) in non_negative {
+            if !value.is_finite() || value < 0.0 {
+                anyhow::bail!("{} must be a non-negative number, got {}", name, value);
+            }
+        }
+
+        let percentages = [
+            ("max_slippage", self.max_slippage),
+            ("stop_loss_percentage", self.stop_loss_percentage),
+            ("max_price_impact_pct", self.max_price_impact_pct),
+        ];
+        for (name, value) in percentages {
+            if !(value > 0.0 && value <= 100.0) {
+                anyhow::bail!("{} must be in (0, 100], got {}", name, value);
+            }
+        }
+
+        if self.max_trades_per_hour == 0 {
+            anyhow::bail!("max_trades_per_hour must be at least 1");
+        }
+        if self.jupiter_slippage_bps > 10_000 {
+            anyhow::bail!("jupiter_slippage_bps must be at most 10000, got {}", self.jupiter_slippage_bps);
+        }
+        Ok(())
+    }
+}
+
+// A partial change to RiskSettings, only the fields that are set are applied
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskSettingsUpdate {
+    pub max_position_size: Option<f64>,
+    pub max_daily_loss: Option<f64>,
+    pub max_slippage: Option<f64>,
+    pub min_profit_threshold: Option<f64>,
+    pub max_trades_per_hour: Option<u32>,
+    pub enable_stop_loss: Option<bool>,
+    pub stop_loss_percentage: Option<f64>,
+    pub max_gas_price: Option<u64>,
+    pub min_liquidity: Option<f64>,
+    pub use_jupiter_for_execution: Option<bool>,
+    pub jupiter_slippage_bps: Option<u16>,
+    pub max_price_impact_pct: Option<f64>,
+}
+
+impl RiskSettingsUpdate {
+    pub fn apply_to(&self, settings: &mut RiskSettings) {
+        if let Some(v) = self.max_position_size {
+            settings.max_position_size = v;
+        }
+        if let Some(v) = self.max_daily_loss {
+            settings.max_daily_loss = v;
+        }
+        if let Some(v) = self.max_slippage {
+            settings.max_slippage = v;
+        }
+        if let Some(v) = self.min_profit_threshold {
+            settings.min_profit_threshold = v;
+        }
+        if let Some(v) = self.max_trades_per_hour {
+            settings.max_trades_per_hour = v;
+        }
+        if let Some(v) = self.enable_stop_loss {
+            settings.enable_stop_loss = v;
+        }
+        if let Some(v) = self.stop_loss_percentage {
+            settings.stop_loss_percentage = v;
+        }
+        if let Some(v) = self.max_gas_price {
+            settings.max_gas_price = v;
+        }
+        if let Some(v) = self.min_liquidity {
+            settings.min_liquidity = v;
+        }
+        if let Some(v) = self.use_jupiter_for_execution {
+            settings.use_jupiter_for_execution = v;
+        }
+        if let Some(v) = self.jupiter_slippage_bps {
+            settings.jupiter_slippage_bps = v;
+        }
+        if let Some(v) = self.max_price_impact_pct {
+            settings.max_price_impact_pct = v;
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +273,7 @@ pub struct MonitoringConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingConfig {
     pub scan_interval_ms: u64,
+    // How long after a scan its opportunities can still be executed
     pub execution_timeout_ms: u64,
     pub max_concurrent_trades: u32,
     pub enable_auto_trading: bool,
@@ -91,10 +281,282 @@ pub struct TradingConfig {
     pub price_update_threshold: f64,
 }
 
+// How trades are routed: Jito bundles first, then plain RPC sends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionConfig {
+    // Failed or expired bundles before giving up on Jito for a trade
+    pub jito_attempts: u32,
+    // RPC sends aren't atomic, a leg can land without the other, so this is opt-in
+    pub rpc_fallback: bool,
+    // Priority fee for RPC-sent transactions when recent fees are unavailable
+    pub compute_unit_price_micro_lamports: u64,
+    // processed, confirmed or finalized
+    pub commitment: String,
+    pub confirm_timeout_ms: u64,
+    // How often an unconfirmed transaction is sent again
+    pub rebroadcast_interval_ms: u64,
+    pub blockhash_refresh_ms: u64,
+    // Times an expired RPC transaction is re-signed with a fresh blockhash
+    pub max_resign_attempts: u32,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            jito_attempts: 2,
+            rpc_fallback: false,
+            compute_unit_price_micro_lamports: 100_000,
+            commitment: "confirmed".to_string(),
+            confirm_timeout_ms: 60_000,
+            rebroadcast_interval_ms: 2_000,
+            blockhash_refresh_ms: 2_000,
+            max_resign_attempts: 2,
+        }
+    }
+}
+
+// Address lookup tables used to compile v0 transactions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LookupTableConfig {
+    // Create and extend our own tables with frequently used accounts. Off by
+    // default since each table costs rent.
+    pub enabled: bool,
+    // Tables we own from earlier runs
+    pub tables: Vec<String>,
+    // JSON list of the tables this bot created, reloaded on startup so they
+    // aren't paid for again
+    pub state_path: String,
+    pub cache_ttl_ms: u64,
+    // Transactions an account has to appear in before it's added to a table
+    pub min_account_uses: u64,
+    pub maintenance_interval_ms: u64,
+    pub max_owned_tables: usize,
+}
+
+impl Default for LookupTableConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tables: Vec::new(),
+            state_path: "lookup_tables.json".to_string(),
+            cache_ttl_ms: 60_000,
+            min_account_uses: 3,
+            maintenance_interval_ms: 60_000,
+            max_owned_tables: 4,
+        }
+    }
+}
+
+// Compute unit limits and prices for transactions we build ourselves
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComputeBudgetConfig {
+    // Simulate to size the limit, otherwise Jupiter's estimate is used
+    pub simulate: bool,
+    // Headroom on top of the units consumed, 0.15 = 15%
+    pub unit_limit_margin: f64,
+    // Runs a route needs on record before simulation is skipped for it
+    pub hot_route_samples: usize,
+    // How long a hot route goes without being simulated again
+    pub hot_route_ttl_ms: u64,
+    // Runs kept per route
+    pub history_size: usize,
+    // Percentile of recent prioritization fees to pay
+    pub priority_fee_percentile: u8,
+    pub max_unit_price_micro_lamports: u64,
+}
+
+impl Default for ComputeBudgetConfig {
+    fn default() -> Self {
+        Self {
+            simulate: true,
+            unit_limit_margin: 0.15,
+            hot_route_samples: 5,
+            hot_route_ttl_ms: 300_000,
+            history_size: 20,
+            priority_fee_percentile: 75,
+            max_unit_price_micro_lamports: 2_000_000,
+        }
+    }
+}
+
+// Local control socket for changing a running bot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdminConfig {
+    pub enabled: bool,
+    // Only the owner can connect, and it should live in a private directory
+    pub socket_path: String,
+    // JSON lines with the old and new values of every risk change
+    pub audit_log_path: String,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            socket_path: default_socket_path(),
+            audit_log_path: "risk_audit.jsonl".to_string(),
+        }
+    }
+}
+
+fn default_socket_path() -> String {
+    let dir = std::env::var("XDG_RUNTIME_DIR")
+        .or_else(|_| std::env::var("HOME"))
+        .unwrap_or_else(|_| ".".to_string());
+    std::path::Path::new(&dir).join("solana-arbitrage-bot.sock").to_string_lossy().into_owned()
+}
+
+// Conditions that halt auto-trading until it's resumed by hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    pub enabled: bool,
+    // Trades in a row that reverted or filled only partly
+    pub max_consecutive_failures: u32,
+    // Shortfall of realized against estimated profit, as a share of the notional
+    pub max_realized_slippage_pct: f64,
+    // Slots the best RPC endpoint in rotation may trail the cluster tip. It
+    // also trips once rpc.max_slot_lag has ejected every endpoint.
+    pub max_slot_lag: u64,
+    // Spread between DEX prices for a pair that's treated as bad data
+    pub max_price_divergence_pct: f64,
+    // How often the wallet balance and RPC lag are checked
+    pub check_interval_ms: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_consecutive_failures: 5,
+            max_realized_slippage_pct: 2.0,
+            max_slot_lag: 150,
+            max_price_divergence_pct: 20.0,
+            check_interval_ms: 10_000,
+        }
+    }
+}
+
+// Mint checks run before a trade touches a token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenScreeningConfig {
+    pub enabled: bool,
+    // Trusted mints skip the mint account checks. USDC and USDT have freeze
+    // authorities, so they'd be rejected otherwise.
+    pub allowlist: Vec<String>,
+    // Never traded
+    pub denylist: Vec<String>,
+    pub reject_freeze_authority: bool,
+    // Token-2022 transfer fee extension
+    pub reject_transfer_fee: bool,
+    // Token-2022 permanent delegate, which can move or burn any holder's tokens
+    pub reject_permanent_delegate: bool,
+    pub cache_ttl_ms: u64,
+}
+
+impl Default for TokenScreeningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowlist: vec![
+                "So11111111111111111111111111111111111111112".to_string(),
+                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB".to_string(),
+            ],
+            denylist: Vec::new(),
+            reject_freeze_authority: true,
+            reject_transfer_fee: true,
+            reject_permanent_delegate: true,
+            cache_ttl_ms: 300_000,
+        }
+    }
+}
+
+// Caps on what a single mint, DEX or pool may carry, counting trades still in
+// flight. Unset caps don't apply.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExposureLimit {
+    // Share of capital_usd in open trades
+    pub max_pct: Option<f64>,
+    pub max_concurrent_trades: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExposureLimitsConfig {
+    pub enabled: bool,
+    // Capital the max_pct limits are a share of. Trades count at their USD value.
+    pub capital_usd: f64,
+    pub default_mint: ExposureLimit,
+    pub default_dex: ExposureLimit,
+    pub default_pool: ExposureLimit,
+    // Overrides of the defaults, keyed by mint address, DEX name and pool address
+    pub mints: HashMap<String, ExposureLimit>,
+    pub dexes: HashMap<String, ExposureLimit>,
+    pub pools: HashMap<String, ExposureLimit>,
+}
+
+impl Default for ExposureLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capital_usd: 10_000.0,
+            default_mint: ExposureLimit {
+                max_pct: Some(50.0),
+                max_concurrent_trades: None,
+            },
+            default_dex: ExposureLimit {
+                max_pct: None,
+                max_concurrent_trades: Some(4),
+            },
+            default_pool: ExposureLimit {
+                max_pct: None,
+                max_concurrent_trades: Some(2),
+            },
+            mints: HashMap::new(),
+            dexes: HashMap::new(),
+            pools: HashMap::new(),
+        }
+    }
+}
+
+// Selling tokens left over when a trade's sell leg fails. The stop loss itself
+// is risk_settings.enable_stop_loss and stop_loss_percentage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnwindConfig {
+    pub enabled: bool,
+    pub check_interval_ms: u64,
+    // Sell at whatever price after this long, even above the stop loss
+    pub max_hold_ms: u64,
+    pub slippage_bps: u16,
+    // Positions still held, reloaded on startup
+    pub state_path: String,
+}
+
+impl Default for UnwindConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_ms: 5_000,
+            max_hold_ms: 600_000,
+            slippage_bps: 100,
+            state_path: "stranded_positions.json".to_string(),
+        }
+    }
+}
+
 impl Config {
     pub fn load(path: &str) -> Result<Self> {
         let content = std::fs::read_to_string(path)?;
         let config: Config = toml::from_str(&content)?;
+        config.risk_settings.validate()?;
         Ok(config)
     }
     
@@ -102,12 +564,15 @@ impl Config {
         Self {
             rpc_endpoints: RpcConfig {
                 primary: "https://api.mainnet-beta.solana.com".to_string(),
+                ws_url: "wss://api.mainnet-beta.solana.com".to_string(),
                 secondary: vec![
                     "https://solana-api.projectserum.com".to_string(),
                     "https://rpc.ankr.com/solana".to_string(),
                 ],
                 timeout_ms: 5000,
                 retry_attempts: 3,
+                health_check_interval_ms: 5000,
+                max_slot_lag: 50,
             },
             dex_endpoints: DexConfig {
                 raydium: DexEndpoint {
@@ -168,16 +633,63 @@ impl Config {
             jito: JitoConfig {
                 enabled: true,
                 tip_account: "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5".to_string(),
+                tip_accounts: vec![
+                    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5".to_string(),
+                    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe".to_string(),
+                    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY".to_string(),
+                    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49".to_string(),
+                    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh".to_string(),
+                    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt".to_string(),
+                    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL".to_string(),
+                    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT".to_string(),
+                ],
                 bundle_endpoint: "https://mainnet.block-engine.jito.wtf".to_string(),
+                block_engines: vec![
+                    BlockEngineConfig {
+                        name: "amsterdam".to_string(),
+                        url: "https://amsterdam.mainnet.block-engine.jito.wtf".to_string(),
+                    },
+                    BlockEngineConfig {
+                        name: "frankfurt".to_string(),
+                        url: "https://frankfurt.mainnet.block-engine.jito.wtf".to_string(),
+                    },
+                    BlockEngineConfig {
+                        name: "ny".to_string(),
+                        url: "https://ny.mainnet.block-engine.jito.wtf".to_string(),
+                    },
+                    BlockEngineConfig {
+                        name: "tokyo".to_string(),
+                        url: "https://tokyo.mainnet.block-engine.jito.wtf".to_string(),
+                    },
+                    BlockEngineConfig {
+                        name: "slc".to_string(),
+                        url: "https://slc.mainnet.block-engine.jito.wtf".to_string(),
+                    },
+                ],
+                fanout_regions: 0,
                 max_tip_lamports: 1_000_000, // 0.001 SOL
                 min_tip_lamports: 100_000,   // 0.0001 SOL
+                bundle_timeout_ms: 30_000,
+                status_poll_interval_ms: 1_000,
+                tip_floor_url: "https://bundles.jito.wtf/api/v1/bundles/tip_floor".to_string(),
+                tip_floor_percentile: 50,
+                tip_floor_refresh_ms: 10_000,
+                tip_profit_fraction: 0.5,
+                simulation_rpc_url: "".to_string(),
+                min_simulated_net_lamports: 0,
             },
             jupiter: JupiterConfig {
                 enabled: true,
-                api_url: "https://quote-api.jup.ag/v6".to_string(),
+                api_url: "https://lite-api.jup.ag/swap/v1".to_string(),
+                endpoints: Vec::new(),
+                endpoint_failure_threshold: 3,
+                endpoint_cooldown_ms: 30_000,
+                price_api_url: "https://lite-api.jup.ag/price/v2".to_string(),
+                tokens_api_url: "https://lite-api.jup.ag/tokens/v1".to_string(),
                 api_key: None,
                 timeout_ms: 10000,
                 retry_attempts: 3,
+                pair_cooldown_ms: 60_000,
                 default_slippage_bps: 50, // 0.5%
                 max_price_impact_pct: 5.0,
                 preferred_dexes: vec![
@@ -190,6 +702,8 @@ impl Config {
                     "Saber".to_string(),
                     "Mercurial".to_string(),
                 ],
+                max_route_hops: 3,
+                max_route_accounts: 64,
                 use_shared_accounts: true,
                 dynamic_compute_unit_limit: true,
                 prioritization_fee_lamports: 100_000, // 0.0001 SOL
@@ -204,6 +718,9 @@ impl Config {
                 stop_loss_percentage: 5.0,
                 max_gas_price: 1_000_000,
                 min_liquidity: 10_000.0,
+                use_jupiter_for_execution: true,
+                jupiter_slippage_bps: 50,
+                max_price_impact_pct: 5.0,
             },
             monitoring: MonitoringConfig {
                 prometheus_port: 9090,
@@ -219,6 +736,14 @@ impl Config {
                 min_opportunity_duration_ms: 500,
                 price_update_threshold: 0.1,
             },
+            execution: ExecutionConfig::default(),
+            lookup_tables: LookupTableConfig::default(),
+            compute_budget: ComputeBudgetConfig::default(),
+            admin: AdminConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            token_screening: TokenScreeningConfig::default(),
+            exposure_limits: ExposureLimitsConfig::default(),
+            unwind: UnwindConfig::default(),
         }
     }
 }
@@ -0,0 +1,265 @@
+use crate::blockhash_manager::BlockhashManager;
+use crate::rpc_pool::RpcPool;
+use crate::transaction_builder::PreparedTransaction;
+use crate::transaction_meta::{fetch_balance_change, BalanceChange};
+use crate::types::ArbitrageError;
+use anyhow::Result;
+use futures_util::StreamExt;
+use metrics::{counter, histogram};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSignatureSubscribeConfig};
+use solana_client::rpc_response::RpcSignatureResult;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::InstructionError,
+    signature::Signature,
+    transaction::{TransactionError, VersionedTransaction},
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// getTransaction can lag the status by a slot or two
+const META_FETCH_ATTEMPTS: u32 = 5;
+
+// Landed slot and on-chain result of a signature
+type SignatureOutcome = (u64, Result<(), TransactionError>);
+
+#[derive(Debug, Clone)]
+pub struct ConfirmedTransaction {
+    pub signature: Signature,
+    // Slot the transaction landed in
+    pub slot: u64,
+    pub change: BalanceChange,
+}
+
+// Sends a signed transaction, rebroadcasts it until it confirms or its blockhash
+// expires, then reads the wallet's balance changes from the confirmed meta
+pub struct ConfirmationTracker {
+    rpc_pool: Arc<RpcPool>,
+    blockhash_manager: Arc<BlockhashManager>,
+    // Empty disables signatureSubscribe, so confirmations are polled
+    ws_url: String,
+    commitment: CommitmentConfig,
+    rebroadcast_interval: Duration,
+    confirm_timeout: Duration,
+}
+
+impl ConfirmationTracker {
+    pub fn new(
+        rpc_pool: Arc<RpcPool>,
+        blockhash_manager: Arc<BlockhashManager>,
+        ws_url: String,
+        commitment: CommitmentConfig,
+        rebroadcast_interval: Duration,
+        confirm_timeout: Duration,
+    ) -> Self {
+        Self {
+            rpc_pool,
+            blockhash_manager,
+            ws_url,
+            commitment,
+            rebroadcast_interval,
+            confirm_timeout,
+        }
+    }
+
+    // Fails with BlockhashExpired only once the transaction can no longer land at
+    // the tracked commitment, so the caller can re-sign it. Hitting
+    // confirm_timeout first fails with ConfirmationTimeout instead.
+    pub async fn send_and_track(
+        &self,
+        prepared: &PreparedTransaction,
+        owner: &str,
+        mint: &str,
+    ) -> Result<ConfirmedTransaction> {
+        let transaction = &prepared.transaction;
+        let signature = transaction.signatures[0];
+        let expiry = self.blockhash_manager.expiry_watch(prepared.last_valid_block_height);
+        let started = Instant::now();
+
+        // Preflight only on the first send, rebroadcasts would just fail it as
+        // "already processed"
+        self.send(transaction, false).await?;
+        debug!("📤 Sent transaction {}", signature);
+
+        let confirmation = self.wait_for_confirmation(&signature);
+        tokio::pin!(confirmation);
+        let mut rebroadcast = tokio::time::interval(self.rebroadcast_interval);
+        rebroadcast.tick().await;
+
+        let outcome = loop {
+            tokio::select! {
+                result = &mut confirmation => break result?,
+                _ = rebroadcast.tick() => {
+                    let timed_out = started.elapsed() >= self.confirm_timeout;
+                    if expiry.is_expired() || timed_out {
+                        // One last look, the subscription may not have caught up
+                        if let Some(status) = self.signature_status(&signature).await? {
+                            break status;
+                        }
+                        // The status and block height have to be read at the same
+                        // commitment, or a landed transaction can look expired
+                        if self
+                            .blockhash_manager
+                            .is_expired_at(prepared.last_valid_block_height, self.commitment)
+                            .await?
+                        {
+                            counter!("transaction_confirmations_total", "outcome" => "expired").increment(1);
+                            warn!("⌛ Transaction {} not confirmed before expiry", signature);
+                            return Err(ArbitrageError::BlockhashExpired(signature.to_string()).into());
+                        }
+                        if timed_out {
+                            counter!("transaction_confirmations_total", "outcome" => "timeout").increment(1);
+                            warn!("⌛ Transaction {} not confirmed after {:?}, blockhash still valid",
+                                  signature, self.confirm_timeout);
+                            return Err(ArbitrageError::ConfirmationTimeout(signature.to_string()).into());
+                        }
+                    }
+
+                    counter!("transaction_rebroadcasts_total").increment(1);
+                    if let Err(e) = self.send(transaction, true).await {
+                        debug!("🔁 Rebroadcast of {} failed: {}", signature, e);
+                    }
+                }
+            }
+        };
+
+        histogram!("transaction_confirmation_ms").record(started.elapsed().as_millis() as f64);
+        let confirmed = self.landed(signature, outcome, owner, mint).await?;
+        info!("✅ Transaction {} reached {:?} in {:?}", signature, self.commitment.commitment, started.elapsed());
+        Ok(confirmed)
+    }
+
+    // After a ConfirmationTimeout: polls the signature, no longer rebroadcasting,
+    // until it lands or its blockhash expires. None if it never landed.
+    pub async fn settle(
+        &self,
+        prepared: &PreparedTransaction,
+        owner: &str,
+        mint: &str,
+    ) -> Result<Option<ConfirmedTransaction>> {
+        let signature = prepared.transaction.signatures[0];
+        let mut poll = tokio::time::interval(STATUS_POLL_INTERVAL);
+        loop {
+            poll.tick().await;
+            // Read before the status, so a transaction that landed in time is seen
+            let expired = self
+                .blockhash_manager
+                .is_expired_at(prepared.last_valid_block_height, self.commitment)
+                .await?;
+            if let Some(outcome) = self.signature_status(&signature).await? {
+                info!("⌛ Transaction {} landed after timing out", signature);
+                return self.landed(signature, outcome, owner, mint).await.map(Some);
+            }
+            if expired {
+                counter!("transaction_confirmations_total", "outcome" => "expired").increment(1);
+                debug!("⌛ Transaction {} expired without landing", signature);
+                return Ok(None);
+            }
+        }
+    }
+
+    // Maps an on-chain failure to its error, or reads the balance changes
+    async fn landed(
+        &self,
+        signature: Signature,
+        (slot, result): SignatureOutcome,
+        owner: &str,
+        mint: &str,
+    ) -> Result<ConfirmedTransaction> {
+        if let Err(e) = result {
+            counter!("transaction_confirmations_total", "outcome" => "failed").increment(1);
+            if matches!(e, TransactionError::InstructionError(_, InstructionError::ComputationalBudgetExceeded)) {
+                return Err(ArbitrageError::ComputeBudgetExceeded(signature.to_string()).into());
+            }
+            return Err(ArbitrageError::TransactionFailed(format!("{}: {}", signature, e)).into());
+        }
+        counter!("transaction_confirmations_total", "outcome" => "confirmed").increment(1);
+
+        let change = self.fetch_change(&signature, owner, mint).await?;
+        Ok(ConfirmedTransaction { signature, slot, change })
+    }
+
+    async fn send(&self, transaction: &VersionedTransaction, skip_preflight: bool) -> Result<Signature> {
+        let config = RpcSendTransactionConfig {
+            skip_preflight,
+            preflight_commitment: Some(self.commitment.commitment),
+            // We rebroadcast ourselves
+            max_retries: Some(0),
+            ..Default::default()
+        };
+        self.rpc_pool.send_transaction(transaction, config).await
+    }
+
+    // Resolves once the signature reaches the commitment, with its slot and the
+    // on-chain error if it failed. Prefers signatureSubscribe, polling if that is
+    // unavailable.
+    async fn wait_for_confirmation(&self, signature: &Signature) -> Result<SignatureOutcome> {
+        if !self.ws_url.is_empty() {
+            match self.subscribe(signature).await {
+                Ok(Some(result)) => return Ok(result),
+                Ok(None) => debug!("🔌 Signature subscription for {} closed, polling", signature),
+                Err(e) => debug!("🔌 signatureSubscribe unavailable, polling: {}", e),
+            }
+        }
+
+        loop {
+            if let Some(status) = self.signature_status(signature).await? {
+                return Ok(status);
+            }
+            tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn subscribe(&self, signature: &Signature) -> Result<Option<SignatureOutcome>> {
+        let client = PubsubClient::new(&self.ws_url).await?;
+        let config = RpcSignatureSubscribeConfig {
+            commitment: Some(self.commitment),
+            enable_received_notification: Some(false),
+        };
+        let (mut notifications, unsubscribe) = client.signature_subscribe(signature, Some(config)).await?;
+
+        let mut result = None;
+        while let Some(notification) = notifications.next().await {
+            if let RpcSignatureResult::ProcessedSignature(processed) = notification.value {
+                result = Some((notification.context.slot, processed.err.map_or(Ok(()), Err)));
+                break;
+            }
+        }
+
+        drop(notifications);
+        unsubscribe().await;
+        Ok(result)
+    }
+
+    async fn signature_status(&self, signature: &Signature) -> Result<Option<SignatureOutcome>> {
+        let signatures = [*signature];
+        let signatures = &signatures;
+        let statuses = self.rpc_pool.read(|rpc| async move { rpc.get_signature_statuses(signatures).await }).await?;
+        Ok(statuses
+            .value
+            .into_iter()
+            .next()
+            .flatten()
+            .filter(|status| status.satisfies_commitment(self.commitment))
+            .map(|status| (status.slot, status.status)))
+    }
+
+    async fn fetch_change(&self, signature: &Signature, owner: &str, mint: &str) -> Result<BalanceChange> {
+        let mut attempt = 1;
+        loop {
+            let rpc = self.rpc_pool.client().await;
+            match fetch_balance_change(&rpc, signature, owner, mint).await {
+                Ok(change) => return Ok(change),
+                Err(e) if attempt < META_FETCH_ATTEMPTS => {
+                    debug!("⏳ Meta for {} not available yet: {}", signature, e);
+                    attempt += 1;
+                    tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
@@ -0,0 +1,835 @@
+use crate::blockhash_manager::ExpiryWatch;
+use crate::config::JitoConfig;
+use crate::types::ArbitrageError;
+use crate::utils::token_account_amount;
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use futures_util::future::join_all;
+use metrics::{counter, gauge, histogram};
+use rand::seq::IndexedRandom;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::{
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::VersionedTransaction,
+};
+use solana_system_interface::instruction as system_instruction;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
+
+// Block engines reject bundles with more transactions than this
+const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+// The published tip accounts rarely change
+const TIP_ACCOUNTS_REFRESH: Duration = Duration::from_secs(3600);
+// Weight of the newest sample in a region's latency average
+const LATENCY_EMA_ALPHA: f64 = 0.2;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// A fetched value and when it was fetched
+type Cached<T> = Arc<RwLock<Option<(Instant, T)>>>;
+
+#[derive(Debug, Clone)]
+pub struct JitoClient {
+    client: Client,
+    config: JitoConfig,
+    block_engines: Arc<Vec<BlockEngine>>,
+    // Block engines that accepted each bundle still awaiting an outcome
+    bundle_regions: Arc<RwLock<HashMap<String, Vec<usize>>>>,
+    tip_floor: Cached<TipFloor>,
+    tip_accounts: Cached<Vec<Pubkey>>,
+}
+
+#[derive(Debug)]
+struct BlockEngine {
+    name: String,
+    url: String,
+    stats: RwLock<RegionStats>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RegionStats {
+    pub submitted: u64,
+    pub accepted: u64,
+    // Landed bundles this region accepted. With fan-out every accepting region
+    // is credited, since the landed copy can't be attributed to one of them.
+    pub landed: u64,
+    pub latency_ema_ms: f64,
+}
+
+impl RegionStats {
+    pub fn landing_rate(&self) -> f64 {
+        if self.accepted == 0 {
+            0.0
+        } else {
+            self.landed as f64 / self.accepted as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RegionStatus {
+    pub name: String,
+    pub url: String,
+    pub stats: RegionStats,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+// Landed tip percentiles over recent bundles, in SOL
+#[derive(Debug, Clone, Deserialize)]
+pub struct TipFloor {
+    pub landed_tips_25th_percentile: f64,
+    pub landed_tips_50th_percentile: f64,
+    pub landed_tips_75th_percentile: f64,
+    pub landed_tips_95th_percentile: f64,
+    pub landed_tips_99th_percentile: f64,
+    pub ema_landed_tips_50th_percentile: f64,
+}
+
+impl TipFloor {
+    // Rounds down to the nearest published percentile
+    pub fn percentile_lamports(&self, percentile: u8) -> u64 {
+        let sol = match percentile {
+            99.. => self.landed_tips_99th_percentile,
+            95..=98 => self.landed_tips_95th_percentile,
+            75..=94 => self.landed_tips_75th_percentile,
+            50..=74 => self.landed_tips_50th_percentile,
+            _ => self.landed_tips_25th_percentile,
+        };
+        (sol * 1e9) as u64
+    }
+}
+
+// What simulateBundle should check before a bundle is paid for: the payer's
+// lamports plus one token account (the quote mint ATA) must grow by min_net.
+#[derive(Debug, Clone)]
+pub struct SimulationGuard {
+    pub token_account: Pubkey,
+    // Quote is wrapped SOL that gets unwrapped, so gains land in the payer's lamports
+    pub native: bool,
+    // In the token account's base units, or lamports when native
+    pub min_net: i128,
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleSimulation {
+    pub succeeded: bool,
+    pub error: Option<String>,
+    pub units_consumed: u64,
+    pub lamport_delta: i64,
+    pub token_delta: i128,
+}
+
+impl BundleSimulation {
+    pub fn net(&self, native: bool) -> i128 {
+        if native {
+            self.token_delta + self.lamport_delta as i128
+        } else {
+            self.token_delta
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatedBundle {
+    // "succeeded", or {"failed": {"error": ..., "tx_signature": ...}}
+    summary: Value,
+    transaction_results: Vec<SimulatedTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatedTransaction {
+    err: Option<Value>,
+    #[serde(default)]
+    pre_execution_accounts: Option<Vec<Option<SimulatedAccount>>>,
+    #[serde(default)]
+    post_execution_accounts: Option<Vec<Option<SimulatedAccount>>>,
+    units_consumed: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SimulatedAccount {
+    lamports: u64,
+    // [base64 data, "base64"]
+    data: (String, String),
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcContextValue<T> {
+    value: T,
+}
+
+// getInflightBundleStatuses entry. Covers roughly the last five minutes.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InflightBundleStatus {
+    pub bundle_id: String,
+    pub status: String,
+    pub landed_slot: Option<u64>,
+}
+
+// getBundleStatuses entry, only available once the bundle has landed
+#[derive(Debug, Clone, Deserialize)]
+pub struct LandedBundleStatus {
+    pub bundle_id: String,
+    pub transactions: Vec<String>,
+    pub slot: u64,
+    pub confirmation_status: Option<String>,
+    pub err: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BundleStatus {
+    Landed,
+    Failed,
+    // Never landed before the timeout, or the block engine no longer knows the bundle
+    Dropped,
+}
+
+impl std::fmt::Display for BundleStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleStatus::Landed => write!(f, "landed"),
+            BundleStatus::Failed => write!(f, "failed"),
+            BundleStatus::Dropped => write!(f, "dropped"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleOutcome {
+    pub bundle_id: String,
+    pub status: BundleStatus,
+    pub landed_slot: Option<u64>,
+    // Signatures of the bundle's transactions, in bundle order
+    pub transactions: Vec<String>,
+}
+
+impl JitoClient {
+    pub fn new(config: JitoConfig) -> Self {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        let mut block_engines: Vec<BlockEngine> = config.block_engines.iter().map(|b| BlockEngine {
+            name: b.name.clone(),
+            url: b.url.trim_end_matches('/').to_string(),
+            stats: RwLock::new(RegionStats::default()),
+        }).collect();
+        let bundle_endpoint = config.bundle_endpoint.trim_end_matches('/').to_string();
+        if !block_engines.iter().any(|b| b.url == bundle_endpoint) {
+            block_engines.push(BlockEngine {
+                name: "default".to_string(),
+                url: bundle_endpoint,
+                stats: RwLock::new(RegionStats::default()),
+            });
+        }
+
+        Self {
+            client,
+            config,
+            block_engines: Arc::new(block_engines),
+            bundle_regions: Arc::new(RwLock::new(HashMap::new())),
+            tip_floor: Arc::new(RwLock::new(None)),
+            tip_accounts: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    // Clamp a requested tip into the configured [min, max] lamport range
+    pub fn clamp_tip(&self, tip_lamports: u64) -> u64 {
+        tip_lamports.clamp(self.config.min_tip_lamports, self.config.max_tip_lamports)
+    }
+
+    pub async fn region_statuses(&self) -> Vec<RegionStatus> {
+        let mut statuses = Vec::with_capacity(self.block_engines.len());
+        for engine in self.block_engines.iter() {
+            statuses.push(RegionStatus {
+                name: engine.name.clone(),
+                url: engine.url.clone(),
+                stats: engine.stats.read().await.clone(),
+            });
+        }
+        statuses
+    }
+
+    // Indices of the block engines a bundle goes to. Regions without a latency
+    // sample sort first so new regions get measured.
+    async fn select_block_engines(&self) -> Vec<usize> {
+        let fanout = self.config.fanout_regions;
+        if fanout == 0 || fanout >= self.block_engines.len() {
+            return (0..self.block_engines.len()).collect();
+        }
+
+        let mut latencies = Vec::with_capacity(self.block_engines.len());
+        for (i, engine) in self.block_engines.iter().enumerate() {
+            latencies.push((i, engine.stats.read().await.latency_ema_ms));
+        }
+        latencies.sort_by(|a, b| a.1.total_cmp(&b.1));
+        latencies.into_iter().take(fanout).map(|(i, _)| i).collect()
+    }
+
+    // Status lookups go to a region that accepted the bundle
+    async fn status_url(&self, bundle_ids: &[String]) -> String {
+        let regions = self.bundle_regions.read().await;
+        let index = bundle_ids
+            .first()
+            .and_then(|id| regions.get(id))
+            .and_then(|accepted| accepted.first().copied())
+            .unwrap_or(0);
+        self.block_engines[index].url.clone()
+    }
+
+    pub async fn get_tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        let url = self.block_engines[0].url.clone();
+        let accounts: Vec<String> = self.rpc_call(&url, "getTipAccounts", "getTipAccounts", json!([])).await?;
+        accounts
+            .iter()
+            .map(|a| Pubkey::from_str(a).map_err(|e| anyhow::anyhow!("Invalid Jito tip account {}: {}", a, e)))
+            .collect()
+    }
+
+    // Tip accounts from the block engine, cached for TIP_ACCOUNTS_REFRESH. Falls
+    // back to tip_accounts/tip_account from config when the lookup fails.
+    pub async fn tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        if let Some((fetched_at, accounts)) = self.tip_accounts.read().await.as_ref() {
+            if fetched_at.elapsed() < TIP_ACCOUNTS_REFRESH {
+                return Ok(accounts.clone());
+            }
+        }
+
+        let accounts = match self.get_tip_accounts().await {
+            Ok(accounts) if !accounts.is_empty() => accounts,
+            Ok(_) => {
+                warn!("⚠️ getTipAccounts returned no accounts, using configured tip accounts");
+                self.configured_tip_accounts()?
+            }
+            Err(e) => {
+                warn!("⚠️ getTipAccounts failed, using configured tip accounts: {}", e);
+                self.configured_tip_accounts()?
+            }
+        };
+
+        *self.tip_accounts.write().await = Some((Instant::now(), accounts.clone()));
+        Ok(accounts)
+    }
+
+    fn configured_tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        let mut accounts = Vec::new();
+        for account in self.config.tip_accounts.iter().chain(std::iter::once(&self.config.tip_account)) {
+            if account.is_empty() {
+                continue;
+            }
+            let pubkey = Pubkey::from_str(account)
+                .map_err(|e| anyhow::anyhow!("Invalid Jito tip account {}: {}", account, e))?;
+            if !accounts.contains(&pubkey) {
+                accounts.push(pubkey);
+            }
+        }
+
+        if accounts.is_empty() {
+            return Err(anyhow::anyhow!("No Jito tip accounts configured"));
+        }
+        Ok(accounts)
+    }
+
+    // Spreads tips across accounts so bundles don't all write-lock the same one
+    pub async fn pick_tip_account(&self) -> Result<Pubkey> {
+        let accounts = self.tip_accounts().await?;
+        accounts
+            .choose(&mut rand::rng())
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("No Jito tip accounts available"))
+    }
+
+    // Latest tip floor, refreshed every tip_floor_refresh_ms. Falls back to the
+    // last known value if the refresh fails.
+    pub async fn tip_floor(&self) -> Option<TipFloor> {
+        let refresh = Duration::from_millis(self.config.tip_floor_refresh_ms);
+        if let Some((fetched_at, floor)) = self.tip_floor.read().await.as_ref() {
+            if fetched_at.elapsed() < refresh {
+                return Some(floor.clone());
+            }
+        }
+
+        match self.fetch_tip_floor().await {
+            Ok(floor) => {
+                *self.tip_floor.write().await = Some((Instant::now(), floor.clone()));
+                Some(floor)
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to fetch Jito tip floor: {}", e);
+                self.tip_floor.read().await.as_ref().map(|(_, floor)| floor.clone())
+            }
+        }
+    }
+
+    async fn fetch_tip_floor(&self) -> Result<TipFloor> {
+        let response = self.client.get(&self.config.tip_floor_url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Tip floor request failed: {}", response.status()));
+        }
+
+        let floors: Vec<TipFloor> = response.json().await?;
+        floors
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Tip floor response was empty"))
+    }
+
+    // Offers tip_profit_fraction of the expected profit, raised to the configured
+    // landed-tip percentile so the bundle stays competitive, then clamped to
+    // [min_tip, max_tip]. Rejects the trade when that tip would eat the whole profit.
+    pub async fn suggest_tip(&self, expected_profit_lamports: u64) -> Result<u64> {
+        let profit_share = (expected_profit_lamports as f64 * self.config.tip_profit_fraction) as u64;
+        let floor = self
+            .tip_floor()
+            .await
+            .map(|floor| floor.percentile_lamports(self.config.tip_floor_percentile))
+            .unwrap_or(0);
+        histogram!("jito_tip_floor_lamports").record(floor as f64);
+
+        let tip = self.clamp_tip(profit_share.max(floor));
+        debug!("💸 Suggested Jito tip {} lamports (profit {}, share {}, floor {})",
+               tip, expected_profit_lamports, profit_share, floor);
+        if tip >= expected_profit_lamports {
+            counter!("jito_tip_rejections_total").increment(1);
+            return Err(ArbitrageError::SimulationRejected(format!(
+                "tip of {} lamports is not below the expected profit of {}", tip, expected_profit_lamports
+            )).into());
+        }
+        Ok(tip)
+    }
+
+    // Signs the swap legs with `payer` and appends a tip transfer to `tip_account`
+    // as the final transaction. Legs keep their own blockhash; the tip
+    // reuses the last leg's so the whole bundle expires together.
+    pub fn build_bundle(
+        &self,
+        payer: &Keypair,
+        legs: Vec<VersionedTransaction>,
+        tip_account: &Pubkey,
+        tip_lamports: u64,
+    ) -> Result<Vec<VersionedTransaction>> {
+        if legs.is_empty() {
+            return Err(anyhow::anyhow!("Cannot build a bundle without transactions"));
+        }
+        if legs.len() >= MAX_BUNDLE_TRANSACTIONS {
+            return Err(anyhow::anyhow!(
+                "Bundle has {} legs, at most {} fit alongside the tip",
+                legs.len(), MAX_BUNDLE_TRANSACTIONS - 1
+            ));
+        }
+
+        let mut bundle = Vec::with_capacity(legs.len() + 1);
+        for leg in legs {
+            bundle.push(VersionedTransaction::try_new(leg.message, &[payer])?);
+        }
+
+        let tip_lamports = self.clamp_tip(tip_lamports);
+        let recent_blockhash = *bundle[bundle.len() - 1].message.recent_blockhash();
+        let tip_instruction = system_instruction::transfer(&payer.pubkey(), tip_account, tip_lamports);
+        let tip_message = v0::Message::try_compile(&payer.pubkey(), &[tip_instruction], &[], recent_blockhash)?;
+        bundle.push(VersionedTransaction::try_new(VersionedMessage::V0(tip_message), &[payer])?);
+
+        debug!("📦 Built bundle with {} transactions, tip {} lamports to {}",
+               bundle.len(), tip_lamports, tip_account);
+        Ok(bundle)
+    }
+
+    // Submits already-signed transactions via sendBundle to the selected regions in
+    // parallel. Every region derives the same bundle ID from the signatures, so
+    // the accepted submissions collapse into one ID.
+    pub async fn send_bundle(&self, bundle: &[VersionedTransaction]) -> Result<String> {
+        let encoded = bundle
+            .iter()
+            .map(|tx| Ok(STANDARD.encode(bincode::serialize(tx)?)))
+            .collect::<Result<Vec<String>>>()?;
+        let params = json!([encoded, { "encoding": "base64" }]);
+
+        let targets = self.select_block_engines().await;
+        let results = join_all(targets.iter().map(|&i| self.send_to_region(i, params.clone()))).await;
+
+        let mut bundle_id: Option<String> = None;
+        let mut accepted = Vec::new();
+        let mut last_error = None;
+        for (index, result) in targets.into_iter().zip(results) {
+            match result {
+                Ok(id) => {
+                    match &bundle_id {
+                        Some(existing) if *existing != id => warn!(
+                            "⚠️ {} returned bundle ID {} instead of {}",
+                            self.block_engines[index].name, id, existing
+                        ),
+                        Some(_) => {}
+                        None => bundle_id = Some(id),
+                    }
+                    accepted.push(index);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(bundle_id) = bundle_id else {
+            return Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No Jito block engines configured")));
+        };
+
+        let regions: Vec<&str> = accepted.iter().map(|&i| self.block_engines[i].name.as_str()).collect();
+        info!("📦 Jito bundle submitted: {} via {}", bundle_id, regions.join(", "));
+        self.bundle_regions.write().await.insert(bundle_id.clone(), accepted);
+        Ok(bundle_id)
+    }
+
+    async fn send_to_region(&self, index: usize, params: Value) -> Result<String> {
+        let engine = &self.block_engines[index];
+        let started = Instant::now();
+        let result: Result<String> = self.rpc_call(&engine.url, "bundles", "sendBundle", params).await;
+        // A failed send counts as a full request timeout, so a region that fails
+        // fast doesn't look like the fastest one
+        let latency_ms = if result.is_ok() {
+            started.elapsed().as_millis() as f64
+        } else {
+            REQUEST_TIMEOUT.as_millis() as f64
+        };
+
+        let mut stats = engine.stats.write().await;
+        stats.submitted += 1;
+        stats.latency_ema_ms = if stats.submitted == 1 {
+            latency_ms
+        } else {
+            LATENCY_EMA_ALPHA * latency_ms + (1.0 - LATENCY_EMA_ALPHA) * stats.latency_ema_ms
+        };
+        let outcome = if result.is_ok() {
+            stats.accepted += 1;
+            "accepted"
+        } else {
+            "rejected"
+        };
+        drop(stats);
+
+        if result.is_ok() {
+            histogram!("jito_region_latency_ms", "region" => engine.name.clone()).record(latency_ms);
+        }
+        counter!("jito_region_submissions_total", "region" => engine.name.clone(), "outcome" => outcome)
+            .increment(1);
+        if let Err(e) = &result {
+            warn!("⚠️ Jito bundle submission to {} failed: {}", engine.name, e);
+        }
+        result
+    }
+
+    // Credits the outcome to every region that accepted the bundle
+    async fn record_region_outcome(&self, outcome: &BundleOutcome, accepted: Vec<usize>) {
+        for index in accepted {
+            let engine = &self.block_engines[index];
+            let mut stats = engine.stats.write().await;
+            if outcome.status == BundleStatus::Landed {
+                stats.landed += 1;
+            }
+            let landing_rate = stats.landing_rate();
+            drop(stats);
+
+            counter!("jito_region_bundles_total", "region" => engine.name.clone(), "status" => outcome.status.to_string())
+                .increment(1);
+            gauge!("jito_region_landing_rate", "region" => engine.name.clone()).set(landing_rate);
+        }
+    }
+
+    pub async fn submit_bundle(
+        &self,
+        payer: &Keypair,
+        legs: Vec<VersionedTransaction>,
+        tip_lamports: u64,
+        guard: Option<&SimulationGuard>,
+    ) -> Result<String> {
+        let tip_account = self.pick_tip_account().await?;
+        let bundle = self.build_bundle(payer, legs, &tip_account, tip_lamports)?;
+
+        if guard.is_some() && self.config.simulation_rpc_url.is_empty() {
+            counter!("jito_simulations_total", "outcome" => "skipped").increment(1);
+            warn!("⚠️ Sending bundle unsimulated, jito.simulation_rpc_url is not set");
+        } else if let Some(guard) = guard {
+            let simulation = self.simulate_bundle(&bundle, &payer.pubkey(), &guard.token_account).await?;
+            if !simulation.succeeded {
+                counter!("jito_simulations_total", "outcome" => "reverted").increment(1);
+                return Err(ArbitrageError::SimulationRejected(format!(
+                    "bundle reverted: {}",
+                    simulation.error.unwrap_or_default()
+                )).into());
+            }
+
+            let net = simulation.net(guard.native);
+            if net < guard.min_net {
+                counter!("jito_simulations_total", "outcome" => "below_threshold").increment(1);
+                return Err(ArbitrageError::SimulationRejected(format!(
+                    "bundle nets {}, below the minimum of {}", net, guard.min_net
+                )).into());
+            }
+
+            counter!("jito_simulations_total", "outcome" => "passed").increment(1);
+            debug!("🧪 Bundle simulation passed: net {}, {} CU", net, simulation.units_consumed);
+        }
+
+        self.send_bundle(&bundle).await
+    }
+
+    // Runs simulateBundle against simulation_rpc_url, capturing the payer and
+    // `token_account` before the first transaction and after the last
+    pub async fn simulate_bundle(
+        &self,
+        bundle: &[VersionedTransaction],
+        payer: &Pubkey,
+        token_account: &Pubkey,
+    ) -> Result<BundleSimulation> {
+        let encoded = bundle
+            .iter()
+            .map(|tx| Ok(STANDARD.encode(bincode::serialize(tx)?)))
+            .collect::<Result<Vec<String>>>()?;
+
+        // One config entry per transaction, only the ends are inspected
+        let watched = json!({
+            "addresses": [payer.to_string(), token_account.to_string()],
+            "encoding": "base64",
+        });
+        let mut pre_configs = vec![Value::Null; bundle.len()];
+        let mut post_configs = vec![Value::Null; bundle.len()];
+        pre_configs[0] = watched.clone();
+        post_configs[bundle.len() - 1] = watched;
+
+        let params = json!([
+            { "encodedTransactions": encoded },
+            {
+                "preExecutionAccountsConfigs": pre_configs,
+                "postExecutionAccountsConfigs": post_configs,
+                "transactionEncoding": "base64",
+                "skipSigVerify": false,
+                "replaceRecentBlockhash": false,
+            }
+        ]);
+        let response: RpcContextValue<SimulatedBundle> = self
+            .json_rpc(&self.config.simulation_rpc_url, "simulateBundle", params)
+            .await?;
+        let simulated = response.value;
+
+        let error = match &simulated.summary {
+            Value::String(summary) if summary == "succeeded" => None,
+            summary => Some(
+                summary
+                    .get("failed")
+                    .and_then(|failed| failed.get("error"))
+                    .unwrap_or(summary)
+                    .to_string(),
+            ),
+        };
+        let error = error.or_else(|| {
+            simulated.transaction_results.iter().find_map(|tx| tx.err.as_ref().map(|e| e.to_string()))
+        });
+
+        let pre = simulated
+            .transaction_results
+            .first()
+            .and_then(|tx| tx.pre_execution_accounts.as_deref())
+            .unwrap_or_default();
+        let post = simulated
+            .transaction_results
+            .last()
+            .and_then(|tx| tx.post_execution_accounts.as_deref())
+            .unwrap_or_default();
+
+        let lamports = |accounts: &[Option<SimulatedAccount>]| {
+            accounts.first().and_then(|a| a.as_ref()).map(|a| a.lamports).unwrap_or(0)
+        };
+        // A missing token account holds nothing, e.g. a WSOL ATA closed by unwrapping
+        let token_amount = |accounts: &[Option<SimulatedAccount>]| {
+            accounts
+                .get(1)
+                .and_then(|a| a.as_ref())
+                .and_then(|a| STANDARD.decode(&a.data.0).ok())
+                .and_then(|data| token_account_amount(&data))
+                .unwrap_or(0)
+        };
+
+        Ok(BundleSimulation {
+            succeeded: error.is_none(),
+            error,
+            units_consumed: simulated.transaction_results.iter().filter_map(|tx| tx.units_consumed).sum(),
+            lamport_delta: lamports(post) as i64 - lamports(pre) as i64,
+            token_delta: token_amount(post) as i128 - token_amount(pre) as i128,
+        })
+    }
+
+    pub async fn get_inflight_bundle_statuses(&self, bundle_ids: &[String]) -> Result<Vec<InflightBundleStatus>> {
+        let url = self.status_url(bundle_ids).await;
+        let response: RpcContextValue<Vec<InflightBundleStatus>> = self
+            .rpc_call(&url, "getInflightBundleStatuses", "getInflightBundleStatuses", json!([bundle_ids]))
+            .await?;
+        Ok(response.value)
+    }
+
+    pub async fn get_bundle_statuses(&self, bundle_ids: &[String]) -> Result<Vec<Option<LandedBundleStatus>>> {
+        let url = self.status_url(bundle_ids).await;
+        let response: RpcContextValue<Vec<Option<LandedBundleStatus>>> = self
+            .rpc_call(&url, "getBundleStatuses", "getBundleStatuses", json!([bundle_ids]))
+            .await?;
+        Ok(response.value)
+    }
+
+    // Polls until the bundle lands, fails, its blockhash expires, or
+    // bundle_timeout_ms passes. Status lookups that error are logged and retried
+    // on the next poll.
+    pub async fn wait_for_bundle(&self, bundle_id: &str, expiry: Option<ExpiryWatch>) -> Result<BundleOutcome> {
+        let outcome = self.poll_bundle(bundle_id, expiry).await;
+        // Dropped whatever the outcome, status lookups are done with it
+        let accepted = self.bundle_regions.write().await.remove(bundle_id);
+        if let (Ok(outcome), Some(accepted)) = (&outcome, accepted) {
+            self.record_region_outcome(outcome, accepted).await;
+        }
+        outcome
+    }
+
+    async fn poll_bundle(&self, bundle_id: &str, expiry: Option<ExpiryWatch>) -> Result<BundleOutcome> {
+        let ids = vec![bundle_id.to_string()];
+        let timeout = Duration::from_millis(self.config.bundle_timeout_ms);
+        let poll_interval = Duration::from_millis(self.config.status_poll_interval_ms);
+        let started = Instant::now();
+
+        while started.elapsed() < timeout {
+            tokio::time::sleep(poll_interval).await;
+
+            let inflight = match self.get_inflight_bundle_statuses(&ids).await {
+                Ok(statuses) => statuses.into_iter().next(),
+                Err(e) => {
+                    warn!("⚠️ Failed to poll bundle {}: {}", bundle_id, e);
+                    continue;
+                }
+            };
+
+            match inflight.as_ref().map(|s| s.status.as_str()) {
+                Some("Landed") => {
+                    let landed_slot = inflight.and_then(|s| s.landed_slot);
+                    return self.landed_outcome(bundle_id, landed_slot).await;
+                }
+                Some("Failed") => {
+                    warn!("❌ Jito bundle {} failed", bundle_id);
+                    return Ok(BundleOutcome {
+                        bundle_id: bundle_id.to_string(),
+                        status: BundleStatus::Failed,
+                        landed_slot: None,
+                        transactions: Vec::new(),
+                    });
+                }
+                // Pending, or Invalid while the block engine has not indexed it yet
+                _ => debug!("⏳ Jito bundle {} still pending", bundle_id),
+            }
+
+            if expiry.as_ref().is_some_and(|e| e.is_expired()) {
+                debug!("⌛ Jito bundle {} blockhash expired", bundle_id);
+                break;
+            }
+        }
+
+        // The inflight view can miss bundles that landed just before the deadline
+        if let Ok(Some(status)) = self.get_bundle_statuses(&ids).await.map(|s| s.into_iter().flatten().next()) {
+            return self.landed_outcome(bundle_id, Some(status.slot)).await;
+        }
+
+        warn!("⌛ Jito bundle {} dropped after {:?}", bundle_id, started.elapsed());
+        Ok(BundleOutcome {
+            bundle_id: bundle_id.to_string(),
+            status: BundleStatus::Dropped,
+            landed_slot: None,
+            transactions: Vec::new(),
+        })
+    }
+
+    // getBundleStatuses can lag the inflight view, so this polls until the
+    // bundle's signatures are indexed rather than report a landing without them
+    async fn landed_outcome(&self, bundle_id: &str, landed_slot: Option<u64>) -> Result<BundleOutcome> {
+        let ids = vec![bundle_id.to_string()];
+        let timeout = Duration::from_millis(self.config.bundle_timeout_ms);
+        let poll_interval = Duration::from_millis(self.config.status_poll_interval_ms);
+        let started = Instant::now();
+
+        let landed = loop {
+            match self.get_bundle_statuses(&ids).await {
+                Ok(statuses) => {
+                    if let Some(landed) = statuses.into_iter().flatten().next() {
+                        break landed;
+                    }
+                }
+                Err(e) => warn!("⚠️ Failed to fetch landed bundle {}: {}", bundle_id, e),
+            }
+            if started.elapsed() >= timeout {
+                return Err(anyhow::anyhow!(
+                    "Jito bundle {} landed in slot {:?} but its signatures were not indexed within {:?}",
+                    bundle_id, landed_slot, timeout
+                ));
+            }
+            debug!("⏳ Waiting for Jito to index landed bundle {}", bundle_id);
+            tokio::time::sleep(poll_interval).await;
+        };
+
+        // err is {"Ok": null} on success
+        let failed = landed.err.as_ref().is_some_and(|err| err.get("Ok").is_none());
+        let status = if failed { BundleStatus::Failed } else { BundleStatus::Landed };
+        info!("📦 Jito bundle {} {} in slot {}", bundle_id, status, landed.slot);
+        Ok(BundleOutcome {
+            bundle_id: bundle_id.to_string(),
+            status,
+            landed_slot: Some(landed.slot),
+            transactions: landed.transactions,
+        })
+    }
+
+    // JSON-RPC against a block engine, e.g. POST {base_url}/api/v1/bundles
+    async fn rpc_call<T: DeserializeOwned>(&self, base_url: &str, path: &str, method: &str, params: Value) -> Result<T> {
+        let url = format!("{}/api/v1/{}", base_url, path);
+        self.json_rpc(&url, method, params).await
+    }
+
+    async fn json_rpc<T: DeserializeOwned>(&self, url: &str, method: &str, params: Value) -> Result<T> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = self.client.post(url).json(&body).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await?;
+            error!("❌ Jito {} request failed ({}): {}", method, status, error_text);
+            return Err(anyhow::anyhow!("Jito {} request failed ({}): {}", method, status, error_text));
+        }
+
+        let rpc_response: JsonRpcResponse<T> = response.json().await?;
+        if let Some(err) = rpc_response.error {
+            error!("❌ Jito {} error {}: {}", method, err.code, err.message);
+            return Err(anyhow::anyhow!("Jito {} error {}: {}", method, err.code, err.message));
+        }
+
+        rpc_response
+            .result
+            .ok_or_else(|| anyhow::anyhow!("Jito {} returned no result", method))
+    }
+}
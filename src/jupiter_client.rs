@@ -1,39 +1,93 @@
-use crate::types::{JupiterQuote, JupiterSwap, SwapRequest, SwapResponse};
+use crate::route_analysis::MintPrice;
+use crate::types::{
+    JupiterConfig, JupiterError, JupiterQuote, JupiterSwap, RoutePlan, SwapMode, SwapRequest,
+    SwapResponse,
+};
 use anyhow::Result;
+use metrics::{counter, gauge, histogram};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
 //updated client
 #[derive(Debug, Clone)]
 pub struct JupiterClient {
     client: Client,
-    base_url: String,
+    // Quote/swap endpoints in failover order
+    endpoints: Arc<Vec<Endpoint>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+    price_api_url: String,
+    tokens_api_url: String,
     api_key: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug)]
+struct Endpoint {
+    name: String,
+    url: String,
+    api_key: Option<String>,
+    health: RwLock<EndpointHealth>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EndpointHealth {
+    pub consecutive_failures: u32,
+    pub unhealthy_until: Option<Instant>,
+    pub requests: u64,
+    pub failures: u64,
+    pub last_latency_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub name: String,
+    pub url: String,
+    pub healthy: bool,
+    pub health: EndpointHealth,
+}
+
+// Sent as a query string, so list parameters are comma-joined
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct JupiterQuoteRequest {
     pub input_mint: String,
     pub output_mint: String,
     pub amount: u64,
     pub slippage_bps: u16,
-    pub swap_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_mode: Option<SwapMode>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "comma_separated")]
     pub dexes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "comma_separated")]
     pub exclude_dexes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub platform_fee_bps: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_accounts: Option<u8>,
 }
 
+fn comma_separated<S: Serializer>(values: &Option<Vec<String>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match values {
+        Some(values) => serializer.serialize_str(&values.join(",")),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct JupiterQuoteResponse {
     pub input_mint: String,
     pub in_amount: String,
     pub output_mint: String,
     pub out_amount: String,
     pub other_amount_threshold: String,
-    pub swap_mode: String,
+    pub swap_mode: SwapMode,
     pub slippage_bps: u16,
     pub platform_fee: Option<PlatformFee>,
     pub price_impact_pct: String,
@@ -43,108 +97,390 @@ pub struct JupiterQuoteResponse {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PlatformFee {
     pub amount: String,
     pub fee_bps: u16,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RoutePlan {
-    pub swap_info: SwapInfo,
-    pub percent: u8,
+impl TryFrom<JupiterQuoteResponse> for JupiterQuote {
+    type Error = anyhow::Error;
+
+    fn try_from(response: JupiterQuoteResponse) -> Result<Self> {
+        Ok(JupiterQuote {
+            input_mint: response.input_mint,
+            in_amount: response.in_amount.parse()?,
+            output_mint: response.output_mint,
+            out_amount: response.out_amount.parse()?,
+            other_amount_threshold: response.other_amount_threshold.parse()?,
+            swap_mode: response.swap_mode,
+            price_impact_pct: response.price_impact_pct.parse()?,
+            route_plan: response.route_plan,
+            context_slot: response.context_slot,
+            time_taken: response.time_taken,
+            slippage_bps: response.slippage_bps,
+        })
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SwapInfo {
-    pub amm_key: String,
-    pub label: String,
-    pub input_mint: String,
-    pub in_amount: String,
-    pub output_mint: String,
-    pub out_amount: String,
-    pub fee_amount: String,
-    pub fee_mint: String,
+// The swap endpoint wants the quote echoed back in its wire format
+impl From<&JupiterQuote> for JupiterQuoteResponse {
+    fn from(quote: &JupiterQuote) -> Self {
+        JupiterQuoteResponse {
+            input_mint: quote.input_mint.clone(),
+            in_amount: quote.in_amount.to_string(),
+            output_mint: quote.output_mint.clone(),
+            out_amount: quote.out_amount.to_string(),
+            other_amount_threshold: quote.other_amount_threshold.to_string(),
+            swap_mode: quote.swap_mode,
+            slippage_bps: quote.slippage_bps,
+            platform_fee: None,
+            price_impact_pct: quote.price_impact_pct.to_string(),
+            route_plan: quote.route_plan.clone(),
+            context_slot: quote.context_slot,
+            time_taken: quote.time_taken,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct JupiterSwapRequest {
     pub quote_response: JupiterQuoteResponse,
     pub user_public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap_and_unwrap_sol: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub dynamic_compute_unit_limit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub prioritization_fee_lamports: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub as_legacy_transaction: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub use_shared_accounts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fee_account: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tracking_account: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub compute_unit_price_micro_lamports: Option<u64>,
-    pub as_versioned_transaction: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct JupiterSwapResponse {
     pub swap_transaction: String,
     pub last_valid_block_height: u64,
+    #[serde(default)]
     pub prioritization_fee_lamports: u64,
+    // Only present when dynamicComputeUnitLimit was requested
+    #[serde(default)]
+    pub compute_unit_limit: u32,
+    pub simulation_error: Option<serde_json::Value>,
+}
+
+// /swap-instructions response: the pieces of the swap transaction, for callers
+// that compile their own. Takes the same body as /swap.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterSwapInstructions {
+    pub token_ledger_instruction: Option<JupiterInstruction>,
+    #[serde(default)]
+    pub compute_budget_instructions: Vec<JupiterInstruction>,
+    #[serde(default)]
+    pub setup_instructions: Vec<JupiterInstruction>,
+    pub swap_instruction: JupiterInstruction,
+    pub cleanup_instruction: Option<JupiterInstruction>,
+    #[serde(default)]
+    pub other_instructions: Vec<JupiterInstruction>,
+    #[serde(default)]
+    pub address_lookup_table_addresses: Vec<String>,
+    #[serde(default)]
     pub compute_unit_limit: u32,
-    pub prioritization_fee_lamports_per_cu: u64,
+    pub simulation_error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterInstruction {
+    pub program_id: String,
+    pub accounts: Vec<JupiterAccountMeta>,
+    // Base64
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterErrorBody {
+    error: Option<String>,
+    error_code: Option<String>,
+}
+
+// Request details used to fill in structured error fields
+#[derive(Debug, Default)]
+pub struct ErrorContext<'a> {
+    pub input_mint: &'a str,
+    pub output_mint: &'a str,
+    pub slippage_bps: u16,
+}
+
+pub fn classify_error(
+    status: u16,
+    retry_after_ms: Option<u64>,
+    body: &str,
+    context: &ErrorContext,
+) -> JupiterError {
+    let parsed: Option<JupiterErrorBody> = serde_json::from_str(body).ok();
+    let message = parsed
+        .as_ref()
+        .and_then(|b| b.error.clone())
+        .unwrap_or_else(|| body.to_string());
+    let code = parsed.and_then(|b| b.error_code);
+
+    if status == 429 {
+        return JupiterError::RateLimited { retry_after_ms };
+    }
+    if status == 401 || status == 403 {
+        return JupiterError::Unauthorized { status, message };
+    }
+
+    match code.as_deref() {
+        Some("COULD_NOT_FIND_ANY_ROUTE")
+        | Some("NO_ROUTES_FOUND")
+        | Some("ROUTE_PLAN_DOES_NOT_CONSUME_ALL_THE_AMOUNT") => JupiterError::NoRouteFound {
+            input_mint: context.input_mint.to_string(),
+            output_mint: context.output_mint.to_string(),
+        },
+        Some("TOKEN_NOT_TRADABLE") => {
+            // The message names the offending mint; default to the input side
+            let mint = if !context.output_mint.is_empty() && message.contains(context.output_mint) {
+                context.output_mint
+            } else {
+                context.input_mint
+            };
+            JupiterError::TokenNotTradable { mint: mint.to_string() }
+        }
+        Some(c) if c.contains("SLIPPAGE") => JupiterError::SlippageExceeded {
+            slippage_bps: context.slippage_bps,
+        },
+        _ => JupiterError::Api { status, code, message },
+    }
+}
+
+async fn error_from_response(response: reqwest::Response, context: &ErrorContext<'_>) -> JupiterError {
+    let status = response.status().as_u16();
+    let retry_after_ms = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|secs| secs * 1000);
+    let body = response.text().await.unwrap_or_default();
+    classify_error(status, retry_after_ms, &body, context)
+}
+
+fn network_error(err: reqwest::Error) -> JupiterError {
+    JupiterError::Network { message: err.to_string() }
 }
 
 impl JupiterClient {
-    pub fn new(base_url: String, api_key: Option<String>) -> Self {
+    pub fn new(config: &JupiterConfig) -> Self {
         let mut headers = reqwest::header::HeaderMap::new();
-        if let Some(key) = &api_key {
-            headers.insert(
-                "Authorization",
-                format!("Bearer {}", key).parse().unwrap(),
-            );
-        }
         headers.insert("Content-Type", "application/json".parse().unwrap());
 
         let client = Client::builder()
             .default_headers(headers)
-            .timeout(std::time::Duration::from_secs(30))
+            .timeout(std::time::Duration::from_millis(config.timeout_ms))
             .build()
             .expect("Failed to create HTTP client");
 
+        // Configured endpoints first, then api_url as the last resort
+        let mut endpoints: Vec<Endpoint> = config.endpoints.iter().map(|e| Endpoint {
+            name: e.name.clone(),
+            url: e.url.trim_end_matches('/').to_string(),
+            api_key: e.api_key.clone().filter(|k| !k.is_empty()),
+            health: RwLock::new(EndpointHealth::default()),
+        }).collect();
+        let api_url = config.api_url.trim_end_matches('/').to_string();
+        if !endpoints.iter().any(|e| e.url == api_url) {
+            endpoints.push(Endpoint {
+                name: "default".to_string(),
+                url: api_url,
+                api_key: config.api_key.clone().filter(|k| !k.is_empty()),
+                health: RwLock::new(EndpointHealth::default()),
+            });
+        }
+        for endpoint in &endpoints {
+            gauge!("jupiter_endpoint_healthy", "endpoint" => endpoint.name.clone()).set(1.0);
+        }
+
         Self {
             client,
-            base_url,
-            api_key,
+            endpoints: Arc::new(endpoints),
+            failure_threshold: config.endpoint_failure_threshold.max(1),
+            cooldown: Duration::from_millis(config.endpoint_cooldown_ms),
+            price_api_url: config.price_api_url.trim_end_matches('/').to_string(),
+            tokens_api_url: config.tokens_api_url.trim_end_matches('/').to_string(),
+            api_key: config.api_key.clone().filter(|k| !k.is_empty()),
         }
     }
 
-    pub async fn get_quote(&self, request: JupiterQuoteRequest) -> Result<JupiterQuote> {
-        debug!("🔍 Getting Jupiter quote for {} -> {}", request.input_mint, request.output_mint);
-        
-        let url = format!("{}/quote", self.base_url);
-        let response = self.client
-            .get(&url)
-            .query(&request)
-            .send()
-            .await?;
+    pub async fn endpoint_statuses(&self) -> Vec<EndpointStatus> {
+        let now = Instant::now();
+        let mut statuses = Vec::with_capacity(self.endpoints.len());
+        for endpoint in self.endpoints.iter() {
+            let health = endpoint.health.read().await.clone();
+            statuses.push(EndpointStatus {
+                name: endpoint.name.clone(),
+                url: endpoint.url.clone(),
+                healthy: health.unhealthy_until.is_none_or(|until| until <= now),
+                health,
+            });
+        }
+        statuses
+    }
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("❌ Jupiter quote request failed: {}", error_text);
-            return Err(anyhow::anyhow!("Jupiter quote request failed: {}", error_text));
+    // Healthy endpoints in configured order, followed by unhealthy ones so a
+    // request is still attempted when every endpoint is cooling down
+    async fn candidate_endpoints(&self) -> Vec<&Endpoint> {
+        let now = Instant::now();
+        let mut healthy = Vec::new();
+        let mut unhealthy = Vec::new();
+        for endpoint in self.endpoints.iter() {
+            match endpoint.health.read().await.unhealthy_until {
+                Some(until) if until > now => unhealthy.push((until, endpoint)),
+                _ => healthy.push(endpoint),
+            }
+        }
+        unhealthy.sort_by_key(|(until, _)| *until);
+        healthy.extend(unhealthy.into_iter().map(|(_, endpoint)| endpoint));
+        healthy
+    }
+
+    async fn record_success(&self, endpoint: &Endpoint, latency_ms: u64) {
+        let mut health = endpoint.health.write().await;
+        if health.unhealthy_until.take().is_some() {
+            info!("✅ Jupiter endpoint {} recovered", endpoint.name);
+            gauge!("jupiter_endpoint_healthy", "endpoint" => endpoint.name.clone()).set(1.0);
+        }
+        health.consecutive_failures = 0;
+        health.requests += 1;
+        health.last_latency_ms = latency_ms;
+    }
+
+    async fn record_failure(&self, endpoint: &Endpoint, latency_ms: u64) {
+        let mut health = endpoint.health.write().await;
+        health.consecutive_failures += 1;
+        health.requests += 1;
+        health.failures += 1;
+        health.last_latency_ms = latency_ms;
+        if health.consecutive_failures >= self.failure_threshold {
+            if health.unhealthy_until.is_none() {
+                warn!("⚠️ Jupiter endpoint {} marked unhealthy after {} failures",
+                      endpoint.name, health.consecutive_failures);
+                gauge!("jupiter_endpoint_healthy", "endpoint" => endpoint.name.clone()).set(0.0);
+            }
+            health.unhealthy_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    // Sends a quote/swap API request, failing over to the next endpoint when one is
+    // unreachable, rate limited or erroring. Request-level errors such as "no route"
+    // are returned as-is since another endpoint would give the same answer.
+    async fn request<T, F>(&self, operation: &'static str, context: &ErrorContext<'_>, build: F) -> Result<T>
+    where
+        T: DeserializeOwned,
+        F: Fn(&Client, &str) -> reqwest::RequestBuilder,
+    {
+        let mut last_error = None;
+
+        for endpoint in self.candidate_endpoints().await {
+            let mut builder = build(&self.client, &endpoint.url);
+            if let Some(key) = &endpoint.api_key {
+                builder = builder.header("x-api-key", key);
+            }
+
+            let started = Instant::now();
+            let result = match builder.send().await {
+                Err(e) => Err(network_error(e)),
+                Ok(response) if !response.status().is_success() => {
+                    Err(error_from_response(response, context).await)
+                }
+                Ok(response) => response.json::<T>().await.map_err(|e| JupiterError::InvalidResponse {
+                    message: e.to_string(),
+                }),
+            };
+            let latency_ms = started.elapsed().as_millis() as u64;
+            histogram!("jupiter_request_latency_ms", "endpoint" => endpoint.name.clone(), "operation" => operation)
+                .record(latency_ms as f64);
+
+            match result {
+                Ok(body) => {
+                    self.record_success(endpoint, latency_ms).await;
+                    counter!("jupiter_requests_total", "endpoint" => endpoint.name.clone(), "operation" => operation, "outcome" => "ok")
+                        .increment(1);
+                    debug!("📡 Jupiter {} served by {} in {}ms", operation, endpoint.name, latency_ms);
+                    return Ok(body);
+                }
+                Err(err) if err.is_endpoint_failure() => {
+                    warn!("⚠️ Jupiter {} failed on {}: {}", operation, endpoint.name, err);
+                    self.record_failure(endpoint, latency_ms).await;
+                    counter!("jupiter_requests_total", "endpoint" => endpoint.name.clone(), "operation" => operation, "outcome" => "failover")
+                        .increment(1);
+                    last_error = Some(err);
+                }
+                Err(err) => {
+                    self.record_success(endpoint, latency_ms).await;
+                    counter!("jupiter_requests_total", "endpoint" => endpoint.name.clone(), "operation" => operation, "outcome" => "rejected")
+                        .increment(1);
+                    return Err(err.into());
+                }
+            }
         }
 
-        let quote_response: JupiterQuoteResponse = response.json().await?;
+        match last_error {
+            Some(err) => Err(err.into()),
+            None => Err(anyhow::anyhow!("No Jupiter endpoints configured")),
+        }
+    }
+
+    pub async fn get_quote(&self, request: JupiterQuoteRequest) -> Result<JupiterQuote> {
+        let requested_mode = request.swap_mode.unwrap_or_default();
+        debug!("🔍 Getting Jupiter {} quote for {} -> {}",
+               requested_mode, request.input_mint, request.output_mint);
         
-        let quote = JupiterQuote {
-            input_mint: quote_response.input_mint,
-            in_amount: quote_response.in_amount.parse()?,
-            output_mint: quote_response.output_mint,
-            out_amount: quote_response.out_amount.parse()?,
-            price_impact_pct: quote_response.price_impact_pct.parse()?,
-            route_plan: quote_response.route_plan,
-            context_slot: quote_response.context_slot,
-            time_taken: quote_response.time_taken,
-            slippage_bps: quote_response.slippage_bps,
+        let context = ErrorContext {
+            input_mint: &request.input_mint,
+            output_mint: &request.output_mint,
+            slippage_bps: request.slippage_bps,
         };
+        let quote_response: JupiterQuoteResponse = self
+            .request("quote", &context, |client, url| {
+                client.get(format!("{}/quote", url)).query(&request)
+            })
+            .await
+            .inspect_err(|e| error!("❌ Jupiter quote request failed: {}", e))?;
+
+        if quote_response.swap_mode != requested_mode {
+            return Err(anyhow::anyhow!(
+                "Jupiter returned a {} quote for a {} request",
+                quote_response.swap_mode, requested_mode
+            ));
+        }
+        
+        let quote = JupiterQuote::try_from(quote_response)?;
 
-        debug!("✅ Jupiter quote received: {} -> {} ({} tokens)", 
-               quote.input_mint, quote.output_mint, quote.out_amount);
+        debug!("✅ Jupiter {} quote received: {} {} -> {} {} (threshold {})",
+               quote.swap_mode, quote.in_amount, quote.input_mint,
+               quote.out_amount, quote.output_mint, quote.other_amount_threshold);
         
         Ok(quote)
     }
@@ -152,20 +488,20 @@ impl JupiterClient {
     pub async fn get_swap_transaction(&self, request: JupiterSwapRequest) -> Result<JupiterSwap> {
         debug!("🔄 Getting Jupiter swap transaction");
         
-        let url = format!("{}/swap", self.base_url);
-        let response = self.client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("❌ Jupiter swap request failed: {}", error_text);
-            return Err(anyhow::anyhow!("Jupiter swap request failed: {}", error_text));
+        let context = ErrorContext {
+            input_mint: &request.quote_response.input_mint,
+            output_mint: &request.quote_response.output_mint,
+            slippage_bps: request.quote_response.slippage_bps,
+        };
+        let swap_response: JupiterSwapResponse = self
+            .request("swap", &context, |client, url| {
+                client.post(format!("{}/swap", url)).json(&request)
+            })
+            .await
+            .inspect_err(|e| error!("❌ Jupiter swap request failed: {}", e))?;
+        if let Some(simulation_error) = &swap_response.simulation_error {
+            warn!("⚠️ Jupiter swap simulation reported an error: {}", simulation_error);
         }
-
-        let swap_response: JupiterSwapResponse = response.json().await?;
         
         let swap = JupiterSwap {
             swap_transaction: swap_response.swap_transaction,
@@ -178,55 +514,125 @@ impl JupiterClient {
         Ok(swap)
     }
 
+    pub async fn get_swap_instructions(&self, request: JupiterSwapRequest) -> Result<JupiterSwapInstructions> {
+        debug!("🔄 Getting Jupiter swap instructions");
+
+        let context = ErrorContext {
+            input_mint: &request.quote_response.input_mint,
+            output_mint: &request.quote_response.output_mint,
+            slippage_bps: request.quote_response.slippage_bps,
+        };
+        let instructions: JupiterSwapInstructions = self
+            .request("swap-instructions", &context, |client, url| {
+                client.post(format!("{}/swap-instructions", url)).json(&request)
+            })
+            .await
+            .inspect_err(|e| error!("❌ Jupiter swap-instructions request failed: {}", e))?;
+        if let Some(simulation_error) = &instructions.simulation_error {
+            warn!("⚠️ Jupiter swap simulation reported an error: {}", simulation_error);
+        }
+
+        Ok(instructions)
+    }
+
     pub async fn get_tokens(&self) -> Result<HashMap<String, TokenInfo>> {
         debug!("🪙 Fetching Jupiter token list");
         
-        let url = format!("{}/tokens", self.base_url);
+        let url = format!("{}/tagged/verified", self.tokens_api_url);
         let response = self.client
             .get(&url)
+            .headers(self.public_api_headers())
             .send()
-            .await?;
+            .await
+            .map_err(network_error)?;
 
         if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("❌ Jupiter tokens request failed: {}", error_text);
-            return Err(anyhow::anyhow!("Jupiter tokens request failed: {}", error_text));
+            let err = error_from_response(response, &ErrorContext::default()).await;
+            error!("❌ Jupiter tokens request failed: {}", err);
+            return Err(err.into());
         }
 
-        let tokens: HashMap<String, TokenInfo> = response.json().await?;
+        let tokens: Vec<TokenInfo> = response.json().await?;
+        let tokens: HashMap<String, TokenInfo> = tokens
+            .into_iter()
+            .map(|token| (token.address.clone(), token))
+            .collect();
         debug!("✅ Fetched {} tokens from Jupiter", tokens.len());
         Ok(tokens)
     }
 
+    fn public_api_headers(&self) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(key) = &self.api_key {
+            headers.insert("x-api-key", key.parse().unwrap());
+        }
+        headers
+    }
+
+    pub async fn get_token(&self, mint: &str) -> Result<TokenInfo> {
+        let url = format!("{}/token/{}", self.tokens_api_url, mint);
+        let response = self.client
+            .get(&url)
+            .headers(self.public_api_headers())
+            .send()
+            .await
+            .map_err(network_error)?;
+
+        if !response.status().is_success() {
+            let context = ErrorContext { input_mint: mint, ..Default::default() };
+            let err = error_from_response(response, &context).await;
+            error!("❌ Jupiter token request failed: {}", err);
+            return Err(err.into());
+        }
+
+        Ok(response.json().await?)
+    }
+
+    // USD price plus decimals per mint, for converting raw amounts such as route fees
+    pub async fn get_mint_prices(&self, mints: &[String]) -> Result<HashMap<String, MintPrice>> {
+        let prices = self.get_price(mints).await?;
+        let mut mint_prices = HashMap::new();
+        for (mint, price_usd) in prices {
+            let token = self.get_token(&mint).await?;
+            mint_prices.insert(mint, MintPrice { price_usd, decimals: token.decimals });
+        }
+        Ok(mint_prices)
+    }
+
     pub async fn get_price(&self, ids: &[String]) -> Result<HashMap<String, f64>> {
         debug!("💰 Getting Jupiter prices for {} tokens", ids.len());
         
-        let url = format!("{}/price", self.base_url);
+        let url = self.price_api_url.clone();
         let response = self.client
             .get(&url)
+            .headers(self.public_api_headers())
             .query(&[("ids", ids.join(","))])
             .send()
-            .await?;
+            .await
+            .map_err(network_error)?;
 
         if !response.status().is_success() {
-            let error_text = response.text().await?;
-            error!("❌ Jupiter price request failed: {}", error_text);
-            return Err(anyhow::anyhow!("Jupiter price request failed: {}", error_text));
+            let err = error_from_response(response, &ErrorContext::default()).await;
+            error!("❌ Jupiter price request failed: {}", err);
+            return Err(err.into());
         }
 
-        let prices: HashMap<String, PriceData> = response.json().await?;
-        let price_map: HashMap<String, f64> = prices
-            .into_iter()
-            .map(|(k, v)| (k, v.price))
-            .collect();
+        // Unknown mints come back as null entries and are left out of the map
+        let prices: PriceResponse = response.json().await?;
+        let mut price_map = HashMap::new();
+        for (mint, data) in prices.data {
+            if let Some(data) = data {
+                price_map.insert(mint, data.price.parse()?);
+            }
+        }
 
         debug!("✅ Fetched prices for {} tokens", price_map.len());
         Ok(price_map)
     }
 
     pub async fn execute_swap(&self, swap_request: SwapRequest) -> Result<SwapResponse> {
-        info!("🚀 Executing Jupiter swap: {} -> {}", 
-              swap_request.input_mint, swap_request.output_mint);
+        info!("🚀 Executing Jupiter {} swap: {} -> {}",
+              swap_request.swap_mode, swap_request.input_mint, swap_request.output_mint);
 
         // Get quote first
         let quote_request = JupiterQuoteRequest {
@@ -234,7 +640,7 @@ impl JupiterClient {
             output_mint: swap_request.output_mint.clone(),
             amount: swap_request.amount,
             slippage_bps: (swap_request.slippage * 100.0) as u16,
-            swap_mode: Some("ExactIn".to_string()),
+            swap_mode: Some(swap_request.swap_mode),
             dexes: swap_request.allowed_dexes,
             exclude_dexes: swap_request.excluded_dexes,
             platform_fee_bps: None,
@@ -245,21 +651,9 @@ impl JupiterClient {
 
         // Create swap transaction
         let swap_request_jupiter = JupiterSwapRequest {
-            quote_response: JupiterQuoteResponse {
-                input_mint: quote.input_mint.clone(),
-                in_amount: quote.in_amount.to_string(),
-                output_mint: quote.output_mint.clone(),
-                out_amount: quote.out_amount.to_string(),
-                other_amount_threshold: "0".to_string(),
-                swap_mode: "ExactIn".to_string(),
-                slippage_bps: quote.slippage_bps,
-                platform_fee: None,
-                price_impact_pct: quote.price_impact_pct.to_string(),
-                route_plan: quote.route_plan.clone(),
-                context_slot: quote.context_slot,
-                time_taken: quote.time_taken,
-            },
+            quote_response: JupiterQuoteResponse::from(&quote),
             user_public_key: swap_request.user_public_key,
+            wrap_and_unwrap_sol: Some(true),
             dynamic_compute_unit_limit: Some(true),
             prioritization_fee_lamports: Some(swap_request.priority_fee),
             as_legacy_transaction: Some(false),
@@ -267,7 +661,6 @@ impl JupiterClient {
             fee_account: None,
             tracking_account: None,
             compute_unit_price_micro_lamports: None,
-            as_versioned_transaction: Some(true),
         };
 
         let swap = self.get_swap_transaction(swap_request_jupiter).await?;
@@ -285,23 +678,36 @@ impl JupiterClient {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// Entry from the tokens v1 API. Note the mix of camelCase and snake_case keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenInfo {
     pub address: String,
-    pub chain_id: u16,
-    pub decimals: u8,
     pub name: String,
     pub symbol: String,
+    pub decimals: u8,
+    #[serde(rename = "logoURI")]
     pub logo_uri: Option<String>,
+    #[serde(default)]
     pub tags: Vec<String>,
+    pub daily_volume: Option<f64>,
+    pub freeze_authority: Option<String>,
+    pub mint_authority: Option<String>,
+    pub permanent_delegate: Option<String>,
     pub extensions: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceResponse {
+    pub data: HashMap<String, Option<PriceData>>,
+    #[serde(default)]
+    pub time_taken: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PriceData {
     pub id: String,
-    pub mint_symbol: String,
-    pub vs_token: String,
-    pub vs_token_symbol: String,
-    pub price: f64,
+    #[serde(rename = "type")]
+    pub price_type: String,
+    pub price: String,
 }
@@ -1,9 +1,21 @@
+pub mod admin;
+pub mod compute_budget;
 pub mod config;
+pub mod confirmation_tracker;
 pub mod arbitrage_engine;
+pub mod blockhash_manager;
+pub mod circuit_breaker;
 pub mod dex_monitor;
 pub mod grpc_server;
 pub mod jito_client;
 pub mod jupiter_client;
+pub mod lookup_table_manager;
+pub mod route_analysis;
+pub mod rpc_pool;
+pub mod stranded_inventory;
+pub mod token_screen;
+pub mod transaction_builder;
+pub mod transaction_meta;
 pub mod risk_manager;
 pub mod portfolio_manager;
 pub mod monitoring;
@@ -0,0 +1,333 @@
+use crate::config::LookupTableConfig;
+use crate::rpc_pool::RpcPool;
+use anyhow::Result;
+use solana_sdk::{
+    address_lookup_table::{
+        instruction::{create_lookup_table, extend_lookup_table},
+        state::{AddressLookupTable, LOOKUP_TABLE_MAX_ADDRESSES},
+        AddressLookupTableAccount,
+    },
+    instruction::Instruction,
+    pubkey::Pubkey,
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+// Keeps an extend transaction under the packet size limit
+const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+// A table costs its 32-byte address plus index bytes in the message, so it only
+// pays off once it replaces at least this many static keys
+const MIN_TABLE_COVERAGE: usize = 2;
+
+#[derive(Debug, Clone)]
+struct CachedTable {
+    table: AddressLookupTableAccount,
+    fetched_at: Instant,
+}
+
+// One transaction's worth of changes to one of our tables
+#[derive(Debug, Clone)]
+pub struct LookupTableUpdate {
+    pub table: Pubkey,
+    pub created: bool,
+    pub addresses: Vec<Pubkey>,
+    pub instructions: Vec<Instruction>,
+}
+
+// Caches lookup table contents, picks the tables a v0 message should use, and
+// grows our own tables with the accounts our routes touch most
+pub struct LookupTableManager {
+    rpc_pool: Arc<RpcPool>,
+    config: LookupTableConfig,
+    cache: RwLock<HashMap<Pubkey, CachedTable>>,
+    // Tables we're the authority of
+    owned: RwLock<Vec<Pubkey>>,
+    // Slot of each table's last extend. New addresses can't be looked up until
+    // a later slot, so the table sits out until then.
+    warming: RwLock<HashMap<Pubkey, u64>>,
+    // Times each account appeared in a transaction we built
+    usage: RwLock<HashMap<Pubkey, u64>>,
+}
+
+impl LookupTableManager {
+    pub fn new(rpc_pool: Arc<RpcPool>, config: LookupTableConfig) -> Self {
+        let mut owned: Vec<Pubkey> = Vec::new();
+        for address in config.tables.iter().chain(load_created_tables(&config.state_path).iter()) {
+            match Pubkey::from_str(address) {
+                Ok(key) if !owned.contains(&key) => owned.push(key),
+                Ok(_) => {}
+                Err(e) => warn!("⚠️ Ignoring invalid lookup table {}: {}", address, e),
+            }
+        }
+
+        Self {
+            rpc_pool,
+            config,
+            cache: RwLock::new(HashMap::new()),
+            owned: RwLock::new(owned),
+            warming: RwLock::new(HashMap::new()),
+            usage: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // Table contents, from the cache when younger than cache_ttl_ms. Missing or
+    // undecodable tables are skipped.
+    pub async fn get_tables(&self, keys: &[Pubkey]) -> Result<Vec<AddressLookupTableAccount>> {
+        let ttl = Duration::from_millis(self.config.cache_ttl_ms);
+        let mut tables = Vec::with_capacity(keys.len());
+        let mut missing = Vec::new();
+        {
+            let cache = self.cache.read().await;
+            for key in keys {
+                match cache.get(key) {
+                    Some(cached) if cached.fetched_at.elapsed() < ttl => tables.push(cached.table.clone()),
+                    _ => missing.push(*key),
+                }
+            }
+        }
+        if missing.is_empty() {
+            return Ok(tables);
+        }
+
+        let missing_ref = &missing;
+        let accounts = self
+            .rpc_pool
+            .read(|rpc| async move { rpc.get_multiple_accounts(missing_ref).await })
+            .await?;
+
+        let mut cache = self.cache.write().await;
+        for (key, account) in missing.into_iter().zip(accounts) {
+            let Some(account) = account else {
+                warn!("⚠️ Address lookup table {} not found", key);
+                continue;
+            };
+            let table = match AddressLookupTable::deserialize(&account.data) {
+                Ok(table) => AddressLookupTableAccount {
+                    key,
+                    addresses: table.addresses.to_vec(),
+                },
+                Err(e) => {
+                    warn!("⚠️ Failed to decode lookup table {}: {}", key, e);
+                    continue;
+                }
+            };
+            cache.insert(key, CachedTable {
+                table: table.clone(),
+                fetched_at: Instant::now(),
+            });
+            tables.push(table);
+        }
+        Ok(tables)
+    }
+
+    pub async fn invalidate(&self, table: &Pubkey) {
+        self.cache.write().await.remove(table);
+    }
+
+    // The tables to compile `instructions` with, chosen from Jupiter's tables
+    // for the route and our own
+    pub async fn tables_for(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        route_tables: &[String],
+    ) -> Result<Vec<AddressLookupTableAccount>> {
+        let mut keys = route_tables
+            .iter()
+            .map(|a| Pubkey::from_str(a))
+            .collect::<Result<Vec<_>, _>>()?;
+        let warming = self.warming_tables().await?;
+        for owned in self.owned.read().await.iter() {
+            if !keys.contains(owned) && !warming.contains(owned) {
+                keys.push(*owned);
+            }
+        }
+
+        let candidates = self.get_tables(&keys).await?;
+        let selected = select_tables(payer, instructions, candidates);
+        debug!("📇 Compiling with {} of {} lookup tables", selected.len(), keys.len());
+        Ok(selected)
+    }
+
+    // Our tables whose last extend slot hasn't passed yet
+    async fn warming_tables(&self) -> Result<HashSet<Pubkey>> {
+        if self.warming.read().await.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let slot = self.rpc_pool.read(|rpc| async move { rpc.get_slot().await }).await?;
+        let mut warming = self.warming.write().await;
+        warming.retain(|_, extended_at| *extended_at >= slot);
+        Ok(warming.keys().copied().collect())
+    }
+
+    pub async fn record_usage(&self, payer: &Pubkey, instructions: &[Instruction]) {
+        let mut usage = self.usage.write().await;
+        for key in lookupable_keys(payer, instructions) {
+            *usage.entry(key).or_default() += 1;
+        }
+    }
+
+    // Create and extend instructions that add the most used accounts missing
+    // from our tables, filling the newest table before creating another
+    pub async fn plan_updates(&self, authority: &Pubkey) -> Result<Vec<LookupTableUpdate>> {
+        let owned_keys = self.owned.read().await.clone();
+        let owned = self.get_tables(&owned_keys).await?;
+        let covered: HashSet<Pubkey> = owned.iter().flat_map(|t| t.addresses.iter().copied()).collect();
+
+        let mut wanted: Vec<(Pubkey, u64)> = self
+            .usage
+            .read()
+            .await
+            .iter()
+            .filter(|(key, uses)| **uses >= self.config.min_account_uses && !covered.contains(key) && *key != authority)
+            .map(|(key, uses)| (*key, *uses))
+            .collect();
+        if wanted.is_empty() {
+            return Ok(Vec::new());
+        }
+        wanted.sort_by_key(|(_, uses)| std::cmp::Reverse(*uses));
+        let mut wanted = wanted.into_iter().map(|(key, _)| key).peekable();
+
+        let mut updates = Vec::new();
+        let mut owned_count = owned_keys.len();
+        let (mut table, mut room) = match owned.last() {
+            Some(last) => (Some(last.key), LOOKUP_TABLE_MAX_ADDRESSES - last.addresses.len()),
+            None => (None, 0),
+        };
+
+        while wanted.peek().is_some() {
+            let mut instructions = Vec::new();
+            let created = room == 0;
+            if created {
+                if owned_count >= self.config.max_owned_tables {
+                    debug!("📇 At {} owned lookup tables, not creating more", owned_count);
+                    break;
+                }
+                // The table address derives from the slot, so one creation per round
+                if updates.iter().any(|u: &LookupTableUpdate| u.created) {
+                    break;
+                }
+                let recent_slot = self.rpc_pool.read(|rpc| async move { rpc.get_slot().await }).await?;
+                let (create, address) = create_lookup_table(*authority, *authority, recent_slot);
+                instructions.push(create);
+                table = Some(address);
+                room = LOOKUP_TABLE_MAX_ADDRESSES;
+                owned_count += 1;
+            }
+            let Some(table) = table else { break };
+
+            let addresses: Vec<Pubkey> = wanted.by_ref().take(room.min(MAX_ADDRESSES_PER_EXTEND)).collect();
+            room -= addresses.len();
+            instructions.push(extend_lookup_table(table, *authority, Some(*authority), addresses.clone()));
+            updates.push(LookupTableUpdate {
+                table,
+                created,
+                addresses,
+                instructions,
+            });
+        }
+        Ok(updates)
+    }
+
+    // Called once an update has confirmed in `slot`. The table is used again
+    // from the slot after.
+    pub async fn apply(&self, update: &LookupTableUpdate, slot: u64) {
+        self.warming.write().await.insert(update.table, slot);
+
+        if update.created {
+            let mut owned = self.owned.write().await;
+            owned.push(update.table);
+            match save_created_tables(&self.config.state_path, &self.config.tables, &owned) {
+                Ok(()) => info!("📇 Created lookup table {}, saved to {}", update.table, self.config.state_path),
+                Err(e) => warn!("⚠️ Created lookup table {} but couldn't save it to {}: {}",
+                                update.table, self.config.state_path, e),
+            }
+        }
+        self.invalidate(&update.table).await;
+
+        let mut usage = self.usage.write().await;
+        for address in &update.addresses {
+            usage.remove(address);
+        }
+    }
+
+    pub async fn owned_tables(&self) -> Vec<Pubkey> {
+        self.owned.read().await.clone()
+    }
+}
+
+fn load_created_tables(path: &str) -> Vec<String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            warn!("⚠️ Failed to read lookup tables from {}: {}", path, e);
+            return Vec::new();
+        }
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        warn!("⚠️ Failed to parse lookup tables in {}: {}", path, e);
+        Vec::new()
+    })
+}
+
+// Tables we own that aren't in the config, written beside the file and renamed
+// over it so a crash can't truncate it
+fn save_created_tables(path: &str, configured: &[String], owned: &[Pubkey]) -> Result<()> {
+    let created: Vec<String> = owned
+        .iter()
+        .map(|table| table.to_string())
+        .filter(|table| !configured.contains(table))
+        .collect();
+    let tmp_path = format!("{}.tmp", path);
+    std::fs::write(&tmp_path, serde_json::to_string_pretty(&created)?)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+// Accounts a v0 message could load from a table: everything but signers and
+// invoked programs, which have to stay static
+fn lookupable_keys(payer: &Pubkey, instructions: &[Instruction]) -> HashSet<Pubkey> {
+    let programs: HashSet<Pubkey> = instructions.iter().map(|ix| ix.program_id).collect();
+    instructions
+        .iter()
+        .flat_map(|ix| ix.accounts.iter())
+        .filter(|meta| !meta.is_signer && meta.pubkey != *payer && !programs.contains(&meta.pubkey))
+        .map(|meta| meta.pubkey)
+        .collect()
+}
+
+// Greedily takes the table covering the most still-static keys until no table
+// covers enough to be worth its own address
+pub fn select_tables(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    mut candidates: Vec<AddressLookupTableAccount>,
+) -> Vec<AddressLookupTableAccount> {
+    let mut uncovered = lookupable_keys(payer, instructions);
+    let mut selected = Vec::new();
+
+    while !uncovered.is_empty() && !candidates.is_empty() {
+        let (best, coverage) = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, table)| (i, table.addresses.iter().filter(|a| uncovered.contains(a)).count()))
+            .max_by_key(|(_, coverage)| *coverage)
+            .unwrap_or((0, 0));
+        if coverage < MIN_TABLE_COVERAGE {
+            break;
+        }
+
+        let table = candidates.swap_remove(best);
+        for address in &table.addresses {
+            uncovered.remove(address);
+        }
+        selected.push(table);
+    }
+    selected
+}
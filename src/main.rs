@@ -1,6 +1,7 @@
 use clap::{Parser, Subcommand};
 use solana_arbitrage_bot::{
-    config::Config,
+    admin::{send_admin_request, AdminRequest, AdminServer, RiskControl},
+    config::{Config, RiskSettingsUpdate},
     arbitrage_engine::ArbitrageEngine,
     dex_monitor::DexMonitor,
     grpc_server::ArbitrageGrpcServer,
@@ -9,10 +10,12 @@ use solana_arbitrage_bot::{
     risk_manager::RiskManager,
     portfolio_manager::PortfolioManager,
     monitoring::MonitoringService,
+    route_analysis::analyze_route,
+    types::SwapMode,
 };
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{info, error};
+use tracing::{info, warn, error};
 //updated fully
 #[derive(Parser)]
 #[command(name = "solana-arbitrage-bot")]
@@ -72,6 +75,52 @@ enum Commands {
         /// Maximum slippage
         #[arg(long)]
         max_slippage: Option<f64>,
+
+        /// Minimum profit threshold
+        #[arg(long)]
+        min_profit: Option<f64>,
+
+        /// Maximum trades per hour
+        #[arg(long)]
+        max_trades_per_hour: Option<u32>,
+
+        /// Enable or disable the stop loss
+        #[arg(long)]
+        enable_stop_loss: Option<bool>,
+
+        /// Stop loss percentage
+        #[arg(long)]
+        stop_loss: Option<f64>,
+
+        /// Maximum compute unit price in micro-lamports
+        #[arg(long)]
+        max_gas_price: Option<u64>,
+
+        /// Minimum pool liquidity
+        #[arg(long)]
+        min_liquidity: Option<f64>,
+
+        /// Execute through Jupiter
+        #[arg(long)]
+        use_jupiter: Option<bool>,
+
+        /// Jupiter slippage in basis points
+        #[arg(long)]
+        jupiter_slippage_bps: Option<u16>,
+
+        /// Maximum price impact percentage
+        #[arg(long)]
+        max_price_impact: Option<f64>,
+
+        /// Also write the new settings to the config file
+        #[arg(long)]
+        persist: bool,
+    },
+    /// Resume auto-trading after the circuit breaker halted it
+    Resume {
+        /// Only show whether the circuit breaker is tripped
+        #[arg(long)]
+        status: bool,
     },
     /// Test Jupiter integration
     TestJupiter {
@@ -83,9 +132,13 @@ enum Commands {
         #[arg(long)]
         output_mint: String,
         
-        /// Amount to swap
+        /// Amount to swap (input amount, or output amount with --exact-out)
         #[arg(long, default_value = "1000000")]
         amount: u64,
+        
+        /// Quote an exact output amount instead of an exact input amount
+        #[arg(long)]
+        exact_out: bool,
     },
 }
 
@@ -107,7 +160,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Initialize services
     let monitoring = Arc::new(MonitoringService::new());
-    let risk_manager = Arc::new(RwLock::new(RiskManager::new(config.risk_settings.clone())));
+    let risk_manager = Arc::new(RwLock::new(RiskManager::new(
+        config.risk_settings.clone(),
+        config.token_screening.clone(),
+        config.exposure_limits.clone(),
+    )));
     let portfolio_manager = Arc::new(PortfolioManager::new(config.clone()));
     let jito_client = if cli.command.is_jito_enabled() {
         Some(Arc::new(JitoClient::new(config.jito.clone())))
@@ -116,14 +173,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let jupiter_client = if config.jupiter.enabled {
-        Some(Arc::new(JupiterClient::new(
-            config.jupiter.api_url.clone(),
-            config.jupiter.api_key.clone(),
-        )))
+        Some(Arc::new(JupiterClient::new(&config.jupiter)))
     } else {
         None
     };
     
+    let risk_control = Arc::new(RiskControl::new(
+        risk_manager.clone(),
+        cli.config.clone(),
+        config.admin.audit_log_path.clone(),
+    ));
+
     let dex_monitor = Arc::new(DexMonitor::new(config.dex_endpoints.clone()));
     let arbitrage_engine = Arc::new(ArbitrageEngine::new(
         config.clone(),
@@ -147,6 +207,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             
             // Start arbitrage engine
             arbitrage_engine.start().await?;
+
+            if config.admin.enabled {
+                let admin_server = Arc::new(AdminServer::new(
+                    config.admin.socket_path.clone(),
+                    risk_control.clone(),
+                    arbitrage_engine.circuit_breaker(),
+                ));
+                admin_server.start().await?;
+            }
             
             if grpc {
                 let grpc_server = ArbitrageGrpcServer::new(
@@ -185,25 +254,87 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 info!("  {}: {:.4} (${:.2})", balance.symbol, balance.amount, balance.value_usd);
             }
         }
-        Commands::Risk { max_position, max_daily_loss, max_slippage } => {
-            let mut risk = risk_manager.write().await;
-            if let Some(pos) = max_position {
-                risk.update_max_position_size(pos);
-                info!("📊 Updated max position size to ${:.2}", pos);
-            }
-            if let Some(loss) = max_daily_loss {
-                risk.update_max_daily_loss(loss);
-                info!("📊 Updated max daily loss to ${:.2}", loss);
+        Commands::Risk {
+            max_position,
+            max_daily_loss,
+            max_slippage,
+            min_profit,
+            max_trades_per_hour,
+            enable_stop_loss,
+            stop_loss,
+            max_gas_price,
+            min_liquidity,
+            use_jupiter,
+            jupiter_slippage_bps,
+            max_price_impact,
+            persist,
+        } => {
+            let update = RiskSettingsUpdate {
+                max_position_size: max_position,
+                max_daily_loss,
+                max_slippage,
+                min_profit_threshold: min_profit,
+                max_trades_per_hour,
+                enable_stop_loss,
+                stop_loss_percentage: stop_loss,
+                max_gas_price,
+                min_liquidity,
+                use_jupiter_for_execution: use_jupiter,
+                jupiter_slippage_bps,
+                max_price_impact_pct: max_price_impact,
+            };
+
+            // Applied to the running bot if there is one, otherwise only the
+            // config file can be changed
+            let request = AdminRequest::UpdateRisk { update: update.clone(), persist };
+            let settings = match send_admin_request(&config.admin.socket_path, &request).await {
+                Ok(response) if response.success => response.settings,
+                Ok(response) => {
+                    error!("❌ {}", response.message);
+                    return Err(response.message.into());
+                }
+                Err(e) if persist => {
+                    warn!("⚠️ No running bot at {} ({}), updating {} only", config.admin.socket_path, e, cli.config);
+                    Some(risk_control.update(&update, true, "cli").await?)
+                }
+                Err(e) => {
+                    error!("❌ No running bot at {}: {}. Pass --persist to update {}",
+                           config.admin.socket_path, e, cli.config);
+                    return Err(e.into());
+                }
+            };
+
+            if let Some(settings) = settings {
+                info!("📊 Max position size ${:.2}, max daily loss ${:.2}, max slippage {:.2}%",
+                      settings.max_position_size, settings.max_daily_loss, settings.max_slippage);
+                info!("📊 Min profit {:.2}%, {} trades/hour, stop loss {} at {:.2}%",
+                      settings.min_profit_threshold, settings.max_trades_per_hour,
+                      settings.enable_stop_loss, settings.stop_loss_percentage);
+                info!("📊 Max gas price {}, min liquidity ${:.2}, Jupiter {} at {} bps, max impact {:.2}%",
+                      settings.max_gas_price, settings.min_liquidity, settings.use_jupiter_for_execution,
+                      settings.jupiter_slippage_bps, settings.max_price_impact_pct);
             }
-            if let Some(slip) = max_slippage {
-                risk.update_max_slippage(slip);
-                info!("📊 Updated max slippage to {:.2}%", slip);
+        }
+        Commands::Resume { status } => {
+            let request = if status { AdminRequest::BreakerStatus } else { AdminRequest::Resume };
+            let response = match send_admin_request(&config.admin.socket_path, &request).await {
+                Ok(response) => response,
+                Err(e) => {
+                    error!("❌ No running bot at {}: {}", config.admin.socket_path, e);
+                    return Err(e.into());
+                }
+            };
+
+            info!("🚦 {}", response.message);
+            if let Some(trip) = response.trip {
+                info!("  Tripped at {}: {}", trip.tripped_at.to_rfc3339(), trip.reason);
             }
         }
-        Commands::TestJupiter { input_mint, output_mint, amount } => {
+        Commands::TestJupiter { input_mint, output_mint, amount, exact_out } => {
             if let Some(jupiter_client) = jupiter_client {
-                info!("🧪 Testing Jupiter integration: {} -> {} (amount: {})", 
-                      input_mint, output_mint, amount);
+                let swap_mode = if exact_out { SwapMode::ExactOut } else { SwapMode::ExactIn };
+                info!("🧪 Testing Jupiter integration: {} -> {} ({} amount: {})", 
+                      input_mint, output_mint, swap_mode, amount);
                 
                 use crate::jupiter_client::JupiterQuoteRequest;
                 let request = JupiterQuoteRequest {
@@ -211,11 +342,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     output_mint: output_mint.clone(),
                     amount,
                     slippage_bps: 50, // 0.5%
-                    swap_mode: Some("ExactIn".to_string()),
+                    swap_mode: Some(swap_mode),
                     dexes: None,
                     exclude_dexes: None,
                     platform_fee_bps: None,
-                    max_accounts: Some(64),
+                    max_accounts: Some(config.jupiter.max_route_accounts),
                 };
 
                 match jupiter_client.get_quote(request).await {
@@ -223,9 +354,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         info!("✅ Jupiter quote received:");
                         info!("  Input: {} {} tokens", quote.in_amount, input_mint);
                         info!("  Output: {} {} tokens", quote.out_amount, output_mint);
+                        match quote.swap_mode {
+                            SwapMode::ExactIn => info!("  Minimum output: {} tokens", quote.min_out_amount()),
+                            SwapMode::ExactOut => info!("  Maximum input: {} tokens", quote.max_in_amount()),
+                        }
                         info!("  Price impact: {:.2}%", quote.price_impact_pct);
                         info!("  Time taken: {:.2}ms", quote.time_taken);
-                        info!("  Route: {} steps", quote.route_plan.len());
+
+                        let mut fee_mints: Vec<String> = quote.route_plan.iter()
+                            .map(|plan| plan.swap_info.fee_mint.clone())
+                            .collect();
+                        fee_mints.sort();
+                        fee_mints.dedup();
+                        let prices = jupiter_client.get_mint_prices(&fee_mints).await.unwrap_or_else(|e| {
+                            warn!("⚠️ Could not price route fees: {}", e);
+                            Default::default()
+                        });
+
+                        match analyze_route(&quote, &prices) {
+                            Ok(route) => {
+                                info!("  Route: {} hops, {} legs, fees ${:.4}{}",
+                                      route.hops, route.legs.len(), route.total_fee_usd,
+                                      if route.unpriced_legs > 0 { " (partial)" } else { "" });
+                                for leg in &route.legs {
+                                    let fee_usd = leg.fee_usd
+                                        .map(|usd| format!("${:.4}", usd))
+                                        .unwrap_or_else(|| "n/a".to_string());
+                                    info!("    {}. {} {}% {} -> {} | fee {} {} ({})",
+                                          leg.step + 1, leg.label, leg.percent, leg.in_amount, leg.out_amount,
+                                          leg.fee_amount, leg.fee_mint, fee_usd);
+                                }
+                            }
+                            Err(e) => warn!("⚠️ Could not analyze route: {}", e),
+                        }
                     }
                     Err(e) => {
                         error!("❌ Jupiter quote failed: {}", e);
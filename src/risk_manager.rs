@@ -0,0 +1,414 @@
+use crate::config::{ExposureLimitsConfig, RiskSettings, TokenScreeningConfig};
+use crate::token_screen::{MintProfile, MintRisk};
+use crate::types::TradeRequest;
+use chrono::{NaiveDate, Utc};
+use metrics::{counter, gauge};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const TRADE_WINDOW: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExposureScope {
+    Mint,
+    Dex,
+    Pool,
+}
+
+impl std::fmt::Display for ExposureScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExposureScope::Mint => write!(f, "mint"),
+            ExposureScope::Dex => write!(f, "DEX"),
+            ExposureScope::Pool => write!(f, "pool"),
+        }
+    }
+}
+
+// What a trade ties up while it's in flight
+#[derive(Debug, Clone, Default)]
+pub struct TradeExposure {
+    // Trade amount in USD
+    pub notional: f64,
+    // Mints held as inventory between the legs
+    pub mints: Vec<String>,
+    pub dexes: Vec<String>,
+    pub pools: Vec<String>,
+}
+
+// Shared with the OpenTrade guards, which release their entry synchronously on drop
+type OpenTrades = Arc<Mutex<HashMap<u64, TradeExposure>>>;
+
+// A trade's exposure held in the risk manager. Dropping it releases the
+// exposure, so a trade that errors or is cancelled can't leave it behind.
+#[derive(Debug)]
+pub struct OpenTrade {
+    id: u64,
+    open_trades: OpenTrades,
+}
+
+impl Drop for OpenTrade {
+    fn drop(&mut self) {
+        let mut open_trades = self.open_trades.lock().unwrap_or_else(PoisonError::into_inner);
+        open_trades.remove(&self.id);
+        gauge!("risk_open_trades").set(open_trades.len() as f64);
+    }
+}
+
+impl TradeExposure {
+    fn keys(&self, scope: ExposureScope) -> &[String] {
+        match scope {
+            ExposureScope::Mint => &self.mints,
+            ExposureScope::Dex => &self.dexes,
+            ExposureScope::Pool => &self.pools,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskRejection {
+    // Realized loss since UTC midnight, in USD
+    DailyLossLimit { realized_loss: f64, limit: f64 },
+    HourlyTradeLimit { trades: u32, limit: u32 },
+    PositionTooLarge { amount: f64, limit: f64 },
+    // In percent
+    SlippageTooHigh { slippage: f64, limit: f64 },
+    TokenDenied { mint: String },
+    RiskyMint { mint: String, risk: MintRisk },
+    // Open notional including this trade
+    ExposureTooHigh { scope: ExposureScope, key: String, exposure: f64, limit: f64 },
+    TooManyOpenTrades { scope: ExposureScope, key: String, open: u32, limit: u32 },
+    StrandedMint { mint: String },
+}
+
+impl RiskRejection {
+    // Metric label for the rejection
+    pub fn reason(&self) -> &'static str {
+        match self {
+            RiskRejection::DailyLossLimit { .. } => "daily_loss",
+            RiskRejection::HourlyTradeLimit { .. } => "hourly_trades",
+            RiskRejection::PositionTooLarge { .. } => "position_size",
+            RiskRejection::SlippageTooHigh { .. } => "slippage",
+            RiskRejection::TokenDenied { .. } => "token_denied",
+            RiskRejection::RiskyMint { risk, .. } => risk.kind(),
+            RiskRejection::ExposureTooHigh { .. } => "exposure",
+            RiskRejection::TooManyOpenTrades { .. } => "open_trades",
+            RiskRejection::StrandedMint { .. } => "stranded",
+        }
+    }
+}
+
+impl std::fmt::Display for RiskRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiskRejection::DailyLossLimit { realized_loss, limit } => {
+                write!(f, "daily loss ${:.2} reached the ${:.2} limit", realized_loss, limit)
+            }
+            RiskRejection::HourlyTradeLimit { trades, limit } => {
+                write!(f, "{} trades in the last hour, limit is {}", trades, limit)
+            }
+            RiskRejection::PositionTooLarge { amount, limit } => {
+                write!(f, "position {:.2} exceeds the {:.2} limit", amount, limit)
+            }
+            RiskRejection::SlippageTooHigh { slippage, limit } => {
+                write!(f, "slippage {:.2}% exceeds the {:.2}% limit", slippage, limit)
+            }
+            RiskRejection::TokenDenied { mint } => write!(f, "mint {} is on the denylist", mint),
+            RiskRejection::RiskyMint { mint, risk } => write!(f, "mint {} has a {}", mint, risk),
+            RiskRejection::ExposureTooHigh { scope, key, exposure, limit } => {
+                write!(f, "{} {} exposure {:.2} would exceed the {:.2} limit", scope, key, exposure, limit)
+            }
+            RiskRejection::TooManyOpenTrades { scope, key, open, limit } => {
+                write!(f, "{} open trades through {} {}, limit is {}", open, scope, key, limit)
+            }
+            RiskRejection::StrandedMint { mint } => write!(f, "mint {} has stranded inventory to unwind", mint),
+        }
+    }
+}
+
+impl std::error::Error for RiskRejection {}
+
+// Pre-trade limits plus the state they need: trades started in the last hour,
+// trades still in flight, mints with stranded inventory, P&L realized since
+// UTC midnight and the prices it was booked at
+#[derive(Debug)]
+pub struct RiskManager {
+    settings: RiskSettings,
+    token_screening: TokenScreeningConfig,
+    exposure_limits: ExposureLimitsConfig,
+    open_trades: OpenTrades,
+    next_trade_id: u64,
+    stranded_mints: HashSet<String>,
+    recent_trades: VecDeque<Instant>,
+    pnl_day: NaiveDate,
+    daily_pnl: f64,
+    // Last known USD price of a whole unit of each mint
+    prices: HashMap<String, f64>,
+}
+
+impl RiskManager {
+    pub fn new(
+        settings: RiskSettings,
+        token_screening: TokenScreeningConfig,
+        exposure_limits: ExposureLimitsConfig,
+    ) -> Self {
+        Self {
+            settings,
+            token_screening,
+            exposure_limits,
+            open_trades: OpenTrades::default(),
+            next_trade_id: 0,
+            stranded_mints: HashSet::new(),
+            recent_trades: VecDeque::new(),
+            pnl_day: Utc::now().date_naive(),
+            daily_pnl: 0.0,
+            prices: HashMap::new(),
+        }
+    }
+
+    pub fn settings(&self) -> &RiskSettings {
+        &self.settings
+    }
+
+    // `mints` are the profiles of every token the trade touches
+    pub fn can_execute_trade(
+        &self,
+        request: &TradeRequest,
+        mints: &[MintProfile],
+        exposure: &TradeExposure,
+    ) -> Result<(), RiskRejection> {
+        let result = self
+            .check(request)
+            .and_then(|_| self.check_mints(mints))
+            .and_then(|_| self.check_stranded(exposure))
+            .and_then(|_| self.check_exposure(exposure));
+        if let Err(rejection) = &result {
+            counter!("risk_rejections_total", "reason" => rejection.reason()).increment(1);
+            warn!("🛡️ Risk check rejected {}: {}", request.opportunity_id, rejection);
+        }
+        result
+    }
+
+    fn check(&self, request: &TradeRequest) -> Result<(), RiskRejection> {
+        let realized_loss = -self.daily_pnl();
+        if realized_loss >= self.settings.max_daily_loss {
+            return Err(RiskRejection::DailyLossLimit {
+                realized_loss,
+                limit: self.settings.max_daily_loss,
+            });
+        }
+
+        let trades = self.trades_last_hour();
+        if trades >= self.settings.max_trades_per_hour {
+            return Err(RiskRejection::HourlyTradeLimit {
+                trades,
+                limit: self.settings.max_trades_per_hour,
+            });
+        }
+
+        if request.amount > self.settings.max_position_size {
+            return Err(RiskRejection::PositionTooLarge {
+                amount: request.amount,
+                limit: self.settings.max_position_size,
+            });
+        }
+
+        if request.max_slippage > self.settings.max_slippage {
+            return Err(RiskRejection::SlippageTooHigh {
+                slippage: request.max_slippage,
+                limit: self.settings.max_slippage,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn check_mints(&self, mints: &[MintProfile]) -> Result<(), RiskRejection> {
+        if !self.token_screening.enabled {
+            return Ok(());
+        }
+
+        for profile in mints {
+            let mint = profile.mint.to_string();
+            if self.token_screening.denylist.contains(&mint) {
+                return Err(RiskRejection::TokenDenied { mint });
+            }
+            if self.token_screening.allowlist.contains(&mint) {
+                continue;
+            }
+
+            let risk = match profile {
+                MintProfile { freeze_authority: Some(authority), .. } if self.token_screening.reject_freeze_authority => {
+                    Some(MintRisk::FreezeAuthority(*authority))
+                }
+                MintProfile { transfer_fee_bps: Some(bps), .. } if self.token_screening.reject_transfer_fee => {
+                    Some(MintRisk::TransferFee { bps: *bps })
+                }
+                MintProfile { permanent_delegate: Some(delegate), .. } if self.token_screening.reject_permanent_delegate => {
+                    Some(MintRisk::PermanentDelegate(*delegate))
+                }
+                _ => None,
+            };
+            if let Some(risk) = risk {
+                return Err(RiskRejection::RiskyMint { mint, risk });
+            }
+        }
+        Ok(())
+    }
+
+    // A stranded position's exposure lasts until it's sold, and its mint's
+    // wallet balance is what the unwind reads, so nothing else may trade it
+    fn check_stranded(&self, exposure: &TradeExposure) -> Result<(), RiskRejection> {
+        match exposure.mints.iter().find(|mint| self.stranded_mints.contains(*mint)) {
+            Some(mint) => Err(RiskRejection::StrandedMint { mint: mint.clone() }),
+            None => Ok(()),
+        }
+    }
+
+    fn check_exposure(&self, exposure: &TradeExposure) -> Result<(), RiskRejection> {
+        if !self.exposure_limits.enabled {
+            return Ok(());
+        }
+
+        let limits = &self.exposure_limits;
+        let open_trades = self.open_trades.lock().unwrap_or_else(PoisonError::into_inner);
+        for (scope, defaults, overrides) in [
+            (ExposureScope::Mint, &limits.default_mint, &limits.mints),
+            (ExposureScope::Dex, &limits.default_dex, &limits.dexes),
+            (ExposureScope::Pool, &limits.default_pool, &limits.pools),
+        ] {
+            for key in exposure.keys(scope) {
+                let limit = overrides.get(key).unwrap_or(defaults);
+                let open: Vec<&TradeExposure> =
+                    open_trades.values().filter(|trade| trade.keys(scope).contains(key)).collect();
+
+                if let Some(max) = limit.max_concurrent_trades {
+                    if open.len() as u32 >= max {
+                        return Err(RiskRejection::TooManyOpenTrades {
+                            scope,
+                            key: key.clone(),
+                            open: open.len() as u32,
+                            limit: max,
+                        });
+                    }
+                }
+                if let Some(max_pct) = limit.max_pct {
+                    let cap = limits.capital_usd * max_pct / 100.0;
+                    let total = open.iter().map(|trade| trade.notional).sum::<f64>() + exposure.notional;
+                    if total > cap {
+                        return Err(RiskRejection::ExposureTooHigh {
+                            scope,
+                            key: key.clone(),
+                            exposure: total,
+                            limit: cap,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_stranded_mints(&mut self, mints: HashSet<String>) {
+        self.stranded_mints = mints;
+    }
+
+    // Holds the trade's exposure until the returned guard is dropped, so
+    // concurrent trades count against each other's limits
+    pub fn open_trade(&mut self, exposure: TradeExposure) -> OpenTrade {
+        let id = self.next_trade_id;
+        self.next_trade_id += 1;
+        let mut open_trades = self.open_trades.lock().unwrap_or_else(PoisonError::into_inner);
+        open_trades.insert(id, exposure);
+        gauge!("risk_open_trades").set(open_trades.len() as f64);
+        OpenTrade {
+            id,
+            open_trades: self.open_trades.clone(),
+        }
+    }
+
+    // Counts a trade against the hourly cap. Called when the trade starts, so
+    // concurrent trades can't all slip under the limit.
+    pub fn record_trade(&mut self) {
+        self.record_trade_at(Instant::now());
+    }
+
+    pub fn record_trade_at(&mut self, now: Instant) {
+        while self.recent_trades.front().is_some_and(|t| now.duration_since(*t) >= TRADE_WINDOW) {
+            self.recent_trades.pop_front();
+        }
+        self.recent_trades.push_back(now);
+        gauge!("risk_trades_last_hour").set(self.recent_trades.len() as f64);
+    }
+
+    // Net of fees and tips, in USD
+    pub fn record_realized_pnl(&mut self, pnl_usd: f64) {
+        self.record_realized_pnl_on(pnl_usd, Utc::now().date_naive());
+    }
+
+    // `today` is the UTC date the P&L was realized on
+    pub fn record_realized_pnl_on(&mut self, pnl_usd: f64, today: NaiveDate) {
+        if today != self.pnl_day {
+            info!("📅 New trading day, realized P&L for {} was ${:.2}", self.pnl_day, self.daily_pnl);
+            self.pnl_day = today;
+            self.daily_pnl = 0.0;
+        }
+        self.daily_pnl += pnl_usd;
+        gauge!("risk_daily_pnl_usd").set(self.daily_pnl);
+
+        if -self.daily_pnl >= self.settings.max_daily_loss {
+            warn!("🛑 Daily loss ${:.2} reached the ${:.2} limit, trading paused until UTC midnight",
+                  -self.daily_pnl, self.settings.max_daily_loss);
+        }
+    }
+
+    pub fn record_prices(&mut self, prices: &HashMap<String, f64>) {
+        self.prices.extend(prices.iter().map(|(mint, price)| (mint.clone(), *price)));
+    }
+
+    // Books amounts in whole units of each mint, e.g. profit in the quote mint
+    // and fees in SOL, at the last recorded prices. If one was never priced,
+    // nothing is booked and its mint is returned.
+    pub fn record_realized_pnl_in(&mut self, amounts: &[(&str, f64)]) -> Result<f64, String> {
+        let mut pnl_usd = 0.0;
+        for (mint, amount) in amounts.iter().filter(|(_, amount)| *amount != 0.0) {
+            let price = self.prices.get(*mint).ok_or_else(|| mint.to_string())?;
+            pnl_usd += amount * price;
+        }
+        self.record_realized_pnl(pnl_usd);
+        Ok(pnl_usd)
+    }
+
+    pub fn daily_pnl(&self) -> f64 {
+        self.daily_pnl_on(Utc::now().date_naive())
+    }
+
+    pub fn daily_pnl_on(&self, today: NaiveDate) -> f64 {
+        if self.pnl_day == today {
+            self.daily_pnl
+        } else {
+            0.0
+        }
+    }
+
+    pub fn trades_last_hour(&self) -> u32 {
+        self.trades_last_hour_at(Instant::now())
+    }
+
+    pub fn trades_last_hour_at(&self, now: Instant) -> u32 {
+        self.recent_trades
+            .iter()
+            .filter(|t| now.saturating_duration_since(**t) < TRADE_WINDOW)
+            .count() as u32
+    }
+
+    // Replaces every limit at once, keeping the current ones if any is invalid
+    pub fn update_settings(&mut self, settings: RiskSettings) -> anyhow::Result<()> {
+        settings.validate()?;
+        info!("🛡️ Risk settings updated: {:?}", settings);
+        self.settings = settings;
+        Ok(())
+    }
+}
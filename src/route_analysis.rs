@@ -0,0 +1,104 @@
+use crate::types::JupiterQuote;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// USD price and decimals for converting raw fee amounts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MintPrice {
+    pub price_usd: f64,
+    pub decimals: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteLeg {
+    // Sequential hop index. Legs sharing a step are splits of the same hop.
+    pub step: usize,
+    pub amm_key: String,
+    pub label: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub percent: u8,
+    pub fee_amount: u64,
+    pub fee_mint: String,
+    pub fee_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteAnalysis {
+    pub legs: Vec<RouteLeg>,
+    pub hops: usize,
+    pub total_fee_usd: f64,
+    // Legs whose fee mint had no price, so total_fee_usd undercounts
+    pub unpriced_legs: usize,
+}
+
+impl RouteAnalysis {
+    pub fn is_split(&self) -> bool {
+        self.legs.iter().any(|leg| leg.percent < 100)
+    }
+
+    // First leg routed through one of `labels` (case-insensitive prefix match, so
+    // "Raydium" also catches "Raydium CLMM")
+    pub fn find_amm(&self, labels: &[String]) -> Option<&RouteLeg> {
+        self.legs.iter().find(|leg| {
+            let leg_label = leg.label.to_lowercase();
+            labels.iter().any(|l| leg_label.starts_with(&l.to_lowercase()))
+        })
+    }
+}
+
+pub fn analyze_route(quote: &JupiterQuote, prices: &HashMap<String, MintPrice>) -> Result<RouteAnalysis> {
+    let mut legs: Vec<RouteLeg> = Vec::with_capacity(quote.route_plan.len());
+    let mut total_fee_usd = 0.0;
+    let mut unpriced_legs = 0;
+
+    for plan in &quote.route_plan {
+        let info = &plan.swap_info;
+        let step = match legs.last() {
+            Some(prev) => {
+                if prev.input_mint == info.input_mint && prev.output_mint == info.output_mint {
+                    prev.step
+                } else {
+                    prev.step + 1
+                }
+            }
+            None => 0,
+        };
+
+        let fee_amount: u64 = info.fee_amount.parse()?;
+        let fee_usd = prices.get(&info.fee_mint).map(|p| {
+            fee_amount as f64 / 10f64.powi(p.decimals as i32) * p.price_usd
+        });
+        match fee_usd {
+            Some(usd) => total_fee_usd += usd,
+            None if fee_amount > 0 => unpriced_legs += 1,
+            None => {}
+        }
+
+        legs.push(RouteLeg {
+            step,
+            amm_key: info.amm_key.clone(),
+            label: info.label.clone(),
+            input_mint: info.input_mint.clone(),
+            output_mint: info.output_mint.clone(),
+            in_amount: info.in_amount.parse()?,
+            out_amount: info.out_amount.parse()?,
+            percent: plan.percent,
+            fee_amount,
+            fee_mint: info.fee_mint.clone(),
+            fee_usd,
+        });
+    }
+
+    let hops = legs.last().map(|leg| leg.step + 1).unwrap_or(0);
+
+    Ok(RouteAnalysis {
+        legs,
+        hops,
+        total_fee_usd,
+        unpriced_legs,
+    })
+}
@@ -0,0 +1,294 @@
+use crate::config::RpcConfig;
+use anyhow::Result;
+use futures_util::future::join_all;
+use metrics::{counter, gauge, histogram};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature, transaction::VersionedTransaction};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+// Roughly 20 seconds behind the tip
+const DEFAULT_MAX_SLOT_LAG: u64 = 50;
+// Weight of the newest sample in the latency and error rate averages
+const HEALTH_EMA_ALPHA: f64 = 0.2;
+// A node erroring on every request ranks as if it were this many times slower
+const ERROR_RATE_PENALTY: f64 = 10.0;
+
+// RPC endpoints from RpcConfig behind one client. Reads go to the healthiest
+// node, sends are broadcast to every node, and nodes lagging the cluster tip
+// are ejected until they catch up.
+pub struct RpcPool {
+    nodes: Vec<RpcNode>,
+    retry_attempts: u32,
+    health_check_interval: Duration,
+    max_slot_lag: u64,
+    is_running: RwLock<bool>,
+}
+
+struct RpcNode {
+    // primary, secondary-1, ... so metric labels don't leak API keys in URLs
+    name: String,
+    url: String,
+    client: Arc<RpcClient>,
+    health: RwLock<NodeHealth>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NodeHealth {
+    pub requests: u64,
+    pub errors: u64,
+    pub latency_ema_ms: f64,
+    pub error_rate: f64,
+    pub slot: u64,
+    // Slots behind the cluster tip, as of the last health check that reached it
+    pub slot_lag: u64,
+    // Whether the last health check got a slot from the node
+    pub reachable: bool,
+    pub ejected: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+    pub name: String,
+    pub url: String,
+    pub health: NodeHealth,
+}
+
+impl RpcPool {
+    pub fn new(config: &RpcConfig, commitment: CommitmentConfig) -> Self {
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let urls = std::iter::once(&config.primary).chain(config.secondary.iter());
+        let nodes = urls
+            .enumerate()
+            .map(|(i, url)| RpcNode {
+                name: if i == 0 { "primary".to_string() } else { format!("secondary-{}", i) },
+                url: url.clone(),
+                client: Arc::new(RpcClient::new_with_timeout_and_commitment(url.clone(), timeout, commitment)),
+                health: RwLock::new(NodeHealth::default()),
+            })
+            .collect();
+
+        Self {
+            nodes,
+            retry_attempts: config.retry_attempts.max(1),
+            health_check_interval: match config.health_check_interval_ms {
+                0 => DEFAULT_HEALTH_CHECK_INTERVAL,
+                ms => Duration::from_millis(ms),
+            },
+            max_slot_lag: match config.max_slot_lag {
+                0 => DEFAULT_MAX_SLOT_LAG,
+                lag => lag,
+            },
+            is_running: RwLock::new(false),
+        }
+    }
+
+    pub async fn start(self: &Arc<Self>) {
+        let mut running = self.is_running.write().await;
+        if *running {
+            return;
+        }
+        *running = true;
+        drop(running);
+
+        info!("🩺 Health checking {} RPC endpoints every {:?}", self.nodes.len(), self.health_check_interval);
+        // Checked once up front so slot lag is known before anything reads it
+        self.check_health().await;
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let start = tokio::time::Instant::now() + pool.health_check_interval;
+            let mut interval = tokio::time::interval_at(start, pool.health_check_interval);
+            loop {
+                interval.tick().await;
+                if !*pool.is_running.read().await {
+                    break;
+                }
+                pool.check_health().await;
+            }
+        });
+    }
+
+    pub async fn stop(&self) {
+        *self.is_running.write().await = false;
+    }
+
+    // Polls every node's slot and ejects the ones that are unreachable or too
+    // far behind the cluster tip. The tip is the highest slot any node has
+    // processed or received shreds for, so it holds even if every node lags.
+    pub async fn check_health(&self) {
+        let polls = join_all(self.nodes.iter().enumerate().map(|(i, node)| async move {
+            let started = Instant::now();
+            let result = node.client.get_slot().await;
+            self.record(i, started.elapsed(), result.is_ok()).await;
+            let shred_slot = node.client.get_max_shred_insert_slot().await.unwrap_or(0);
+            (result, shred_slot)
+        }))
+        .await;
+
+        let tip = polls
+            .iter()
+            .map(|(slot, shred_slot)| *slot.as_ref().unwrap_or(&0).max(shred_slot))
+            .max()
+            .unwrap_or(0);
+        for (node, (slot, _)) in self.nodes.iter().zip(polls) {
+            let mut health = node.health.write().await;
+            let was_ejected = health.ejected;
+            match slot {
+                Ok(slot) => {
+                    health.slot = slot;
+                    health.slot_lag = tip.saturating_sub(slot);
+                    health.reachable = true;
+                    health.ejected = health.slot_lag > self.max_slot_lag;
+                }
+                Err(e) => {
+                    debug!("🩺 Health check of {} failed: {}", node.name, e);
+                    health.reachable = false;
+                    health.ejected = true;
+                }
+            }
+
+            gauge!("rpc_slot_lag", "endpoint" => node.name.clone()).set(health.slot_lag as f64);
+            gauge!("rpc_node_healthy", "endpoint" => node.name.clone()).set(if health.ejected { 0.0 } else { 1.0 });
+            if health.ejected && !was_ejected {
+                warn!("🚫 Ejecting RPC {} ({} slots behind)", node.name, health.slot_lag);
+            } else if was_ejected && !health.ejected {
+                info!("✅ RPC {} back in rotation", node.name);
+            }
+        }
+    }
+
+    async fn record(&self, index: usize, elapsed: Duration, ok: bool) {
+        let node = &self.nodes[index];
+        let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        let mut health = node.health.write().await;
+        health.requests += 1;
+        health.latency_ema_ms = if health.requests == 1 {
+            elapsed_ms
+        } else {
+            HEALTH_EMA_ALPHA * elapsed_ms + (1.0 - HEALTH_EMA_ALPHA) * health.latency_ema_ms
+        };
+        let sample = if ok { 0.0 } else { 1.0 };
+        health.error_rate = HEALTH_EMA_ALPHA * sample + (1.0 - HEALTH_EMA_ALPHA) * health.error_rate;
+
+        histogram!("rpc_request_ms", "endpoint" => node.name.clone()).record(elapsed_ms);
+        if !ok {
+            health.errors += 1;
+            counter!("rpc_errors_total", "endpoint" => node.name.clone()).increment(1);
+        }
+    }
+
+    // Node indices, healthiest first. If every node is ejected the least
+    // lagging ones are used rather than failing outright.
+    async fn ranked(&self) -> Vec<usize> {
+        let mut healthy = Vec::with_capacity(self.nodes.len());
+        let mut ejected = Vec::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            let health = node.health.read().await;
+            if health.ejected {
+                ejected.push((i, health.slot_lag as f64));
+            } else {
+                healthy.push((i, health.latency_ema_ms * (1.0 + ERROR_RATE_PENALTY * health.error_rate)));
+            }
+        }
+
+        let mut ranked = if healthy.is_empty() { ejected } else { healthy };
+        ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+        ranked.into_iter().map(|(i, _)| i).collect()
+    }
+
+    // The healthiest node, for callers that need an RpcClient directly
+    pub async fn client(&self) -> Arc<RpcClient> {
+        let best = self.ranked().await.first().copied().unwrap_or(0);
+        self.nodes[best].client.clone()
+    }
+
+    // Runs a read against the healthiest node, failing over to the next one up
+    // to retry_attempts times
+    pub async fn read<T, E, F, Fut>(&self, request: F) -> Result<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+        E: Into<anyhow::Error>,
+    {
+        let mut last_error = None;
+        for index in self.ranked().await.into_iter().take(self.retry_attempts as usize) {
+            let started = Instant::now();
+            let result = request(self.nodes[index].client.clone()).await;
+            self.record(index, started.elapsed(), result.is_ok()).await;
+            match result {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    let e = e.into();
+                    warn!("⚠️ RPC {} request failed, failing over: {}", self.nodes[index].name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No RPC endpoints configured")))
+    }
+
+    // Sends to every node in rotation at once. Succeeds if any of them accepted
+    // the transaction.
+    pub async fn send_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSendTransactionConfig,
+    ) -> Result<Signature> {
+        let targets = self.ranked().await;
+        let results = join_all(targets.iter().map(|&index| async move {
+            let started = Instant::now();
+            let result = self.nodes[index].client.send_transaction_with_config(transaction, config).await;
+            self.record(index, started.elapsed(), result.is_ok()).await;
+            (index, result)
+        }))
+        .await;
+
+        let mut first_error = None;
+        let mut signature = None;
+        for (index, result) in results {
+            match result {
+                Ok(sent) => signature = Some(sent),
+                Err(e) => {
+                    debug!("📤 Send via {} failed: {}", self.nodes[index].name, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match (signature, first_error) {
+            (Some(signature), _) => Ok(signature),
+            (None, Some(e)) => Err(e.into()),
+            (None, None) => Err(anyhow::anyhow!("No RPC endpoints configured")),
+        }
+    }
+
+    // Lag of the most up to date endpoint in rotation. None if the last health
+    // check found none, or none has run yet.
+    pub async fn best_slot_lag(&self) -> Option<u64> {
+        let mut best = None;
+        for node in &self.nodes {
+            let health = node.health.read().await;
+            if health.reachable && !health.ejected {
+                best = Some(best.map_or(health.slot_lag, |lag: u64| lag.min(health.slot_lag)));
+            }
+        }
+        best
+    }
+
+    pub async fn statuses(&self) -> Vec<NodeStatus> {
+        let mut statuses = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            statuses.push(NodeStatus {
+                name: node.name.clone(),
+                url: node.url.clone(),
+                health: node.health.read().await.clone(),
+            });
+        }
+        statuses
+    }
+}
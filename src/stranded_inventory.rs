@@ -0,0 +1,190 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use metrics::gauge;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+// Tokens bought by a leg whose counterpart never landed, waiting to be sold
+// back into the quote mint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrandedPosition {
+    pub mint: String,
+    pub quote_mint: String,
+    // Of `mint`, to read its balance from the portfolio
+    pub decimals: u8,
+    // Base units of `mint` held
+    pub amount: u64,
+    // Base units of `quote_mint` spent buying them
+    pub cost: u64,
+    pub stranded_at: DateTime<Utc>,
+}
+
+impl StrandedPosition {
+    // Loss of `value` against the cost, in percent. Negative is a gain.
+    pub fn loss_pct(&self, value: u64) -> f64 {
+        if self.cost == 0 {
+            return 0.0;
+        }
+        (self.cost as f64 - value as f64) / self.cost as f64 * 100.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnwindReason {
+    // The position is worth its cost again
+    Recovered,
+    StopLoss { loss_pct: f64, limit_pct: f64 },
+    // Held longer than max_hold_ms without recovering
+    MaxHold,
+}
+
+impl UnwindReason {
+    // Metric label for the unwind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            UnwindReason::Recovered => "recovered",
+            UnwindReason::StopLoss { .. } => "stop_loss",
+            UnwindReason::MaxHold => "max_hold",
+        }
+    }
+}
+
+impl std::fmt::Display for UnwindReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnwindReason::Recovered => write!(f, "back at cost"),
+            UnwindReason::StopLoss { loss_pct, limit_pct } => {
+                write!(f, "loss {:.2}% crossed the {:.2}% stop loss", loss_pct, limit_pct)
+            }
+            UnwindReason::MaxHold => write!(f, "held too long"),
+        }
+    }
+}
+
+// Whether to sell a position now, given what it would fetch. `stop_loss_pct`
+// is None when the stop loss is disabled, in which case only recovery or
+// max_hold end the wait.
+pub fn unwind_decision(
+    position: &StrandedPosition,
+    value: u64,
+    stop_loss_pct: Option<f64>,
+    max_hold: Duration,
+) -> Option<UnwindReason> {
+    let loss_pct = position.loss_pct(value);
+    if loss_pct <= 0.0 {
+        return Some(UnwindReason::Recovered);
+    }
+    if let Some(limit_pct) = stop_loss_pct {
+        if loss_pct >= limit_pct {
+            return Some(UnwindReason::StopLoss { loss_pct, limit_pct });
+        }
+    }
+    let held = (Utc::now() - position.stranded_at).to_std().unwrap_or_default();
+    if held >= max_hold {
+        return Some(UnwindReason::MaxHold);
+    }
+    None
+}
+
+// Stranded positions by held and quote mint. Several failed trades in the same
+// pair add up to one position. Saved to `state_path` on every change, so a
+// restart picks up where the last run left off.
+pub struct StrandedInventory {
+    state_path: String,
+    positions: RwLock<HashMap<(String, String), StrandedPosition>>,
+}
+
+impl StrandedInventory {
+    pub fn new(state_path: String) -> Self {
+        let positions: HashMap<_, _> = load_positions(&state_path)
+            .into_iter()
+            .map(|position| ((position.mint.clone(), position.quote_mint.clone()), position))
+            .collect();
+        if !positions.is_empty() {
+            info!("🧳 Loaded {} stranded positions from {}", positions.len(), state_path);
+        }
+        gauge!("stranded_positions").set(positions.len() as f64);
+        Self {
+            state_path,
+            positions: RwLock::new(positions),
+        }
+    }
+
+    pub async fn add(&self, position: StrandedPosition) {
+        warn!("🧳 Holding {} units of {} bought for {} units of {}",
+              position.amount, position.mint, position.cost, position.quote_mint);
+        let mut positions = self.positions.write().await;
+        let key = (position.mint.clone(), position.quote_mint.clone());
+        positions
+            .entry(key)
+            .and_modify(|held| {
+                held.amount += position.amount;
+                held.cost += position.cost;
+            })
+            .or_insert(position);
+        gauge!("stranded_positions").set(positions.len() as f64);
+        self.save(&positions);
+    }
+
+    pub async fn positions(&self) -> Vec<StrandedPosition> {
+        self.positions.read().await.values().cloned().collect()
+    }
+
+    // Mints held in a stranded position, whatever they were bought with
+    pub async fn mints(&self) -> HashSet<String> {
+        self.positions.read().await.keys().map(|(mint, _)| mint.clone()).collect()
+    }
+
+    // Takes `amount` of a position off the books, keeping the cost of the
+    // rest proportional
+    pub async fn reduce(&self, mint: &str, quote_mint: &str, amount: u64) {
+        let mut positions = self.positions.write().await;
+        let key = (mint.to_string(), quote_mint.to_string());
+        if let Some(position) = positions.get_mut(&key) {
+            if amount >= position.amount {
+                positions.remove(&key);
+            } else {
+                let remaining = position.amount - amount;
+                position.cost = (position.cost as u128 * remaining as u128 / position.amount as u128) as u64;
+                position.amount = remaining;
+            }
+        }
+        gauge!("stranded_positions").set(positions.len() as f64);
+        self.save(&positions);
+    }
+
+    // A failed save only costs the positions after a restart, so it's logged
+    // rather than failing the change
+    fn save(&self, positions: &HashMap<(String, String), StrandedPosition>) {
+        if let Err(e) = save_positions(&self.state_path, positions.values()) {
+            warn!("⚠️ Couldn't save stranded positions to {}: {}", self.state_path, e);
+        }
+    }
+}
+
+fn load_positions(path: &str) -> Vec<StrandedPosition> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            warn!("⚠️ Failed to read stranded positions from {}: {}", path, e);
+            return Vec::new();
+        }
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        warn!("⚠️ Failed to parse stranded positions in {}: {}", path, e);
+        Vec::new()
+    })
+}
+
+// Written beside the file and renamed over it so a crash can't truncate it
+fn save_positions<'a>(path: &str, positions: impl Iterator<Item = &'a StrandedPosition>) -> Result<()> {
+    let positions: Vec<&StrandedPosition> = positions.collect();
+    let tmp_path = format!("{}.tmp", path);
+    std::fs::write(&tmp_path, serde_json::to_string_pretty(&positions)?)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
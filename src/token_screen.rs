@@ -0,0 +1,190 @@
+use crate::rpc_pool::RpcPool;
+use crate::utils::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::debug;
+
+// SPL mint layout: mint authority (COption, 36), supply (8), decimals (1),
+// is_initialized (1), freeze authority (COption, 36)
+const MINT_LEN: usize = 82;
+const DECIMALS_OFFSET: usize = 44;
+const FREEZE_AUTHORITY_OFFSET: usize = 46;
+// Token-2022 pads mints with extensions to the token account length, then
+// writes an account type byte followed by type-length-value extensions
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+// TransferFeeConfig: two authorities (64) and withheld amount (8), then the
+// older and newer TransferFee of epoch (8), maximum fee (8) and basis points (2)
+const OLDER_TRANSFER_FEE_BPS_OFFSET: usize = 88;
+const NEWER_TRANSFER_FEE_BPS_OFFSET: usize = 106;
+
+// What the mint account allows its authorities to do to holders
+#[derive(Debug, Clone, PartialEq)]
+pub struct MintProfile {
+    pub mint: Pubkey,
+    pub token_2022: bool,
+    pub decimals: u8,
+    pub freeze_authority: Option<Pubkey>,
+    // Highest of the current and scheduled fee, if the mint charges one
+    pub transfer_fee_bps: Option<u16>,
+    pub permanent_delegate: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MintRisk {
+    FreezeAuthority(Pubkey),
+    TransferFee { bps: u16 },
+    PermanentDelegate(Pubkey),
+}
+
+impl MintRisk {
+    // Metric label for the risk
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MintRisk::FreezeAuthority(_) => "freeze_authority",
+            MintRisk::TransferFee { .. } => "transfer_fee",
+            MintRisk::PermanentDelegate(_) => "permanent_delegate",
+        }
+    }
+}
+
+impl std::fmt::Display for MintRisk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MintRisk::FreezeAuthority(authority) => write!(f, "freeze authority {}", authority),
+            MintRisk::TransferFee { bps } => write!(f, "transfer fee of {} bps", bps),
+            MintRisk::PermanentDelegate(delegate) => write!(f, "permanent delegate {}", delegate),
+        }
+    }
+}
+
+pub fn parse_mint(mint: Pubkey, owner: &Pubkey, data: &[u8]) -> Result<MintProfile> {
+    let token_2022 = if *owner == TOKEN_2022_PROGRAM_ID {
+        true
+    } else if *owner == TOKEN_PROGRAM_ID {
+        false
+    } else {
+        return Err(anyhow::anyhow!("{} is owned by {}, not a token program", mint, owner));
+    };
+    if data.len() < MINT_LEN {
+        return Err(anyhow::anyhow!("{} is not a mint account ({} bytes)", mint, data.len()));
+    }
+
+    let mut profile = MintProfile {
+        mint,
+        token_2022,
+        decimals: data[DECIMALS_OFFSET],
+        freeze_authority: read_coption_pubkey(&data[FREEZE_AUTHORITY_OFFSET..MINT_LEN]),
+        transfer_fee_bps: None,
+        permanent_delegate: None,
+    };
+    if !token_2022 || data.len() <= ACCOUNT_TYPE_OFFSET {
+        return Ok(profile);
+    }
+    if data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+        return Err(anyhow::anyhow!("{} is a Token-2022 account but not a mint", mint));
+    }
+
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let extension = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = data
+            .get(offset + 4..offset + 4 + length)
+            .ok_or_else(|| anyhow::anyhow!("{} has a truncated extension {}", mint, extension))?;
+        match extension {
+            // Zeroed space after the last extension
+            0 => break,
+            EXTENSION_TRANSFER_FEE_CONFIG => {
+                let bps = |at: usize| value.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+                let (older, newer) = bps(OLDER_TRANSFER_FEE_BPS_OFFSET)
+                    .zip(bps(NEWER_TRANSFER_FEE_BPS_OFFSET))
+                    .ok_or_else(|| anyhow::anyhow!("{} has a malformed transfer fee extension", mint))?;
+                // The extension with both fees at zero takes nothing
+                profile.transfer_fee_bps = Some(older.max(newer)).filter(|bps| *bps > 0);
+            }
+            EXTENSION_PERMANENT_DELEGATE => {
+                profile.permanent_delegate = value
+                    .get(..32)
+                    .map(|key| Pubkey::new_from_array(key.try_into().unwrap()))
+                    .filter(|key| *key != Pubkey::default());
+            }
+            _ => {}
+        }
+        offset += 4 + length;
+    }
+    Ok(profile)
+}
+
+// COption<Pubkey>: a u32 tag, 1 if present, then the key
+fn read_coption_pubkey(data: &[u8]) -> Option<Pubkey> {
+    if data[..4] != [1, 0, 0, 0] {
+        return None;
+    }
+    Some(Pubkey::new_from_array(data[4..36].try_into().unwrap()))
+}
+
+// Fetches and caches mint profiles. Authorities and extensions rarely change,
+// so profiles are kept for cache_ttl.
+pub struct TokenScreener {
+    rpc_pool: Arc<RpcPool>,
+    cache_ttl: Duration,
+    cache: RwLock<HashMap<Pubkey, (MintProfile, Instant)>>,
+}
+
+impl TokenScreener {
+    pub fn new(rpc_pool: Arc<RpcPool>, cache_ttl: Duration) -> Self {
+        Self {
+            rpc_pool,
+            cache_ttl,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // Profiles in the order of `mints`. Fails if any mint can't be fetched or
+    // decoded, since an unknown mint can't be cleared for trading.
+    pub async fn profiles(&self, mints: &[Pubkey]) -> Result<Vec<MintProfile>> {
+        let mut missing = Vec::new();
+        {
+            let cache = self.cache.read().await;
+            for mint in mints {
+                if cache.get(mint).is_none_or(|(_, fetched_at)| fetched_at.elapsed() >= self.cache_ttl) {
+                    missing.push(*mint);
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            let missing_ref = &missing;
+            let accounts = self
+                .rpc_pool
+                .read(|rpc| async move { rpc.get_multiple_accounts(missing_ref).await })
+                .await?;
+
+            let mut cache = self.cache.write().await;
+            for (mint, account) in missing.iter().zip(accounts) {
+                let account = account.ok_or_else(|| anyhow::anyhow!("Mint {} not found", mint))?;
+                let profile = parse_mint(*mint, &account.owner, &account.data)?;
+                debug!("🔎 Mint {}: {:?}", mint, profile);
+                cache.insert(*mint, (profile, Instant::now()));
+            }
+        }
+
+        let cache = self.cache.read().await;
+        mints
+            .iter()
+            .map(|mint| {
+                cache
+                    .get(mint)
+                    .map(|(profile, _)| profile.clone())
+                    .ok_or_else(|| anyhow::anyhow!("Mint {} not found", mint))
+            })
+            .collect()
+    }
+}
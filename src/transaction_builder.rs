@@ -0,0 +1,208 @@
+use crate::blockhash_manager::BlockhashManager;
+use crate::compute_budget::{ComputeBudgetPlanner, MAX_COMPUTE_UNIT_LIMIT};
+use crate::jupiter_client::{JupiterInstruction, JupiterSwapInstructions};
+use crate::lookup_table_manager::LookupTableManager;
+use crate::rpc_pool::RpcPool;
+use crate::types::ArbitrageError;
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::VersionedTransaction,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::debug;
+
+// Used when Jupiter didn't simulate a compute unit limit
+const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 600_000;
+
+// A signed transaction and the block height its blockhash is valid through
+#[derive(Debug, Clone)]
+pub struct PreparedTransaction {
+    pub transaction: VersionedTransaction,
+    pub last_valid_block_height: u64,
+}
+
+// Turns Jupiter swap instructions into signed v0 transactions
+pub struct TransactionBuilder {
+    rpc_pool: Arc<RpcPool>,
+    blockhash_manager: Arc<BlockhashManager>,
+    lookup_tables: Arc<LookupTableManager>,
+    compute_budget: Arc<ComputeBudgetPlanner>,
+}
+
+impl TransactionBuilder {
+    pub fn new(
+        rpc_pool: Arc<RpcPool>,
+        blockhash_manager: Arc<BlockhashManager>,
+        lookup_tables: Arc<LookupTableManager>,
+        compute_budget: Arc<ComputeBudgetPlanner>,
+    ) -> Self {
+        Self {
+            rpc_pool,
+            blockhash_manager,
+            lookup_tables,
+            compute_budget,
+        }
+    }
+
+    pub fn compute_budget_instructions(unit_limit: u32, unit_price_micro_lamports: u64) -> Vec<Instruction> {
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(unit_price_micro_lamports),
+        ]
+    }
+
+    // Our compute budget replaces Jupiter's, followed by its setup, the swap
+    // and cleanup. Setup creates every token account the route needs,
+    // idempotently and under the right token program for each mint. Fails if
+    // compute units cost more than `max_unit_price` micro-lamports.
+    pub async fn build_swap_transaction(
+        &self,
+        payer: &Keypair,
+        swap: &JupiterSwapInstructions,
+        route_key: &str,
+        max_unit_price: u64,
+    ) -> Result<PreparedTransaction> {
+        let jupiter_instructions = swap
+            .token_ledger_instruction
+            .iter()
+            .chain(swap.setup_instructions.iter())
+            .chain(std::iter::once(&swap.swap_instruction))
+            .chain(swap.cleanup_instruction.iter())
+            .chain(swap.other_instructions.iter());
+        let instructions = jupiter_instructions.map(to_instruction).collect::<Result<Vec<_>>>()?;
+
+        self.lookup_tables.record_usage(&payer.pubkey(), &instructions).await;
+        let tables = self
+            .lookup_tables
+            .tables_for(&payer.pubkey(), &instructions, &swap.address_lookup_table_addresses)
+            .await?;
+
+        let unit_price = self.compute_budget.unit_price(&writable_accounts(&instructions)).await;
+        if unit_price > max_unit_price {
+            debug!("⛽ CU price {} micro-lamports is above the {} limit", unit_price, max_unit_price);
+            return Err(ArbitrageError::GasPriceTooHigh.into());
+        }
+        let unit_limit = match self.compute_budget.known_limit(route_key).await {
+            Some(limit) => limit,
+            None if self.compute_budget.simulation_enabled() => {
+                self.simulate_unit_limit(payer, &instructions, &tables, unit_price, route_key).await?
+            }
+            None => match swap.compute_unit_limit {
+                0 => DEFAULT_COMPUTE_UNIT_LIMIT,
+                limit => limit,
+            },
+        };
+
+        let mut budgeted = Self::compute_budget_instructions(unit_limit, unit_price);
+        budgeted.extend(instructions);
+        self.compile_and_sign(payer, &budgeted, &tables).await
+    }
+
+    // Simulates with the maximum limit and sizes the real one from the units
+    // consumed, recording them for the route
+    async fn simulate_unit_limit(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+        tables: &[AddressLookupTableAccount],
+        unit_price: u64,
+        route_key: &str,
+    ) -> Result<u32> {
+        let mut budgeted = Self::compute_budget_instructions(MAX_COMPUTE_UNIT_LIMIT, unit_price);
+        budgeted.extend_from_slice(instructions);
+        let prepared = self.compile_and_sign(payer, &budgeted, tables).await?;
+
+        let transaction = &prepared.transaction;
+        let simulation = self
+            .rpc_pool
+            .read(|rpc| async move {
+                let config = RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    ..Default::default()
+                };
+                rpc.simulate_transaction_with_config(transaction, config).await
+            })
+            .await?
+            .value;
+
+        if let Some(err) = simulation.err {
+            debug!("🧪 Simulation logs: {:?}", simulation.logs.unwrap_or_default());
+            return Err(ArbitrageError::SimulationRejected(format!("swap simulation failed: {}", err)).into());
+        }
+        let units_consumed = simulation
+            .units_consumed
+            .ok_or_else(|| anyhow::anyhow!("Simulation didn't report units consumed"))?;
+        self.compute_budget.record_simulation(route_key, units_consumed).await;
+
+        let limit = self.compute_budget.limit_with_margin(units_consumed);
+        debug!("🧪 Route {} consumed {} CUs, limit {}", route_key, units_consumed, limit);
+        Ok(limit)
+    }
+
+    pub async fn compile_and_sign(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<PreparedTransaction> {
+        let recent = self.blockhash_manager.get().await?;
+        let message = v0::Message::try_compile(&payer.pubkey(), instructions, lookup_tables, recent.blockhash)?;
+        Ok(PreparedTransaction {
+            transaction: VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer])?,
+            last_valid_block_height: recent.last_valid_block_height,
+        })
+    }
+
+    // Swaps in the current blockhash and signs again, for a transaction that
+    // expired before confirming
+    pub async fn resign(&self, payer: &Keypair, prepared: &PreparedTransaction) -> Result<PreparedTransaction> {
+        let recent = self.blockhash_manager.refresh().await?;
+        let mut message = prepared.transaction.message.clone();
+        message.set_recent_blockhash(recent.blockhash);
+        Ok(PreparedTransaction {
+            transaction: VersionedTransaction::try_new(message, &[payer])?,
+            last_valid_block_height: recent.last_valid_block_height,
+        })
+    }
+}
+
+// Accounts whose recent prioritization fees price our compute units
+fn writable_accounts(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts = Vec::new();
+    for meta in instructions.iter().flat_map(|ix| ix.accounts.iter()) {
+        if meta.is_writable && !meta.is_signer && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+    accounts
+}
+
+pub fn to_instruction(instruction: &JupiterInstruction) -> Result<Instruction> {
+    let accounts = instruction
+        .accounts
+        .iter()
+        .map(|meta| {
+            Ok(AccountMeta {
+                pubkey: Pubkey::from_str(&meta.pubkey)?,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Instruction {
+        program_id: Pubkey::from_str(&instruction.program_id)?,
+        accounts,
+        data: STANDARD.decode(&instruction.data)?,
+    })
+}
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiTransactionEncoding, UiTransactionStatusMeta,
+    UiTransactionTokenBalance,
+};
+
+// Balance effects of a confirmed transaction on one wallet
+#[derive(Debug, Clone, Default)]
+pub struct BalanceChange {
+    pub fee_lamports: u64,
+    // Change in the wallet's SOL balance, fees included
+    pub lamport_delta: i64,
+    // Change in the wallet's token balance for the requested mint, in base units
+    pub token_delta: i128,
+    pub compute_units_consumed: u64,
+}
+
+impl BalanceChange {
+    pub fn merge(&mut self, other: &BalanceChange) {
+        self.fee_lamports += other.fee_lamports;
+        self.lamport_delta += other.lamport_delta;
+        self.token_delta += other.token_delta;
+        self.compute_units_consumed += other.compute_units_consumed;
+    }
+}
+
+// The wallet is the fee payer, so its lamports sit at account index 0
+pub fn balance_change(meta: &UiTransactionStatusMeta, owner: &str, mint: &str) -> BalanceChange {
+    let lamport_delta = match (meta.pre_balances.first(), meta.post_balances.first()) {
+        (Some(pre), Some(post)) => *post as i64 - *pre as i64,
+        _ => 0,
+    };
+
+    let token_total = |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| -> i128 {
+        match balances {
+            OptionSerializer::Some(balances) => balances
+                .iter()
+                .filter(|b| b.mint == mint && matches!(&b.owner, OptionSerializer::Some(o) if o == owner))
+                .filter_map(|b| b.ui_token_amount.amount.parse::<i128>().ok())
+                .sum(),
+            _ => 0,
+        }
+    };
+
+    BalanceChange {
+        fee_lamports: meta.fee,
+        lamport_delta,
+        token_delta: token_total(&meta.post_token_balances) - token_total(&meta.pre_token_balances),
+        compute_units_consumed: match meta.compute_units_consumed {
+            OptionSerializer::Some(units) => units,
+            _ => 0,
+        },
+    }
+}
+
+pub async fn fetch_balance_change(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+    owner: &str,
+    mint: &str,
+) -> Result<BalanceChange> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let transaction = rpc_client.get_transaction_with_config(signature, config).await?;
+    let meta = transaction
+        .transaction
+        .meta
+        .ok_or_else(|| anyhow::anyhow!("Transaction {} has no status meta", signature))?;
+
+    Ok(balance_change(&meta, owner, mint))
+}
@@ -43,7 +43,8 @@ pub struct TradeRequest {
     pub max_slippage: f64,
     pub priority_fee: i32,
     pub use_jito: bool,
-    pub jito_tip: String,
+    // Tip in lamports. 0 lets the Jito tip strategy size it from expected profit.
+    pub jito_tip: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,15 +56,57 @@ pub struct TradeResponse {
     pub gas_used: f64,
     pub execution_time: i64,
     pub bundle_id: String,
+    pub landed_slot: u64,
+    // landed / failed / dropped for Jito bundles, empty otherwise
+    pub bundle_status: String,
+    // "jito" or "rpc", empty when nothing was sent
+    pub execution_path: String,
 }
 
 // New Jupiter-specific types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SwapMode {
+    #[default]
+    ExactIn,
+    ExactOut,
+}
+
+impl SwapMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapMode::ExactIn => "ExactIn",
+            SwapMode::ExactOut => "ExactOut",
+        }
+    }
+}
+
+impl std::fmt::Display for SwapMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for SwapMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ExactIn" => Ok(SwapMode::ExactIn),
+            "ExactOut" => Ok(SwapMode::ExactOut),
+            _ => Err(format!("Unknown swap mode: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JupiterQuote {
     pub input_mint: String,
     pub in_amount: u64,
     pub output_mint: String,
     pub out_amount: u64,
+    // ExactIn: minimum output after slippage. ExactOut: maximum input after slippage.
+    pub other_amount_threshold: u64,
+    pub swap_mode: SwapMode,
     pub price_impact_pct: f64,
     pub route_plan: Vec<RoutePlan>,
     pub context_slot: u64,
@@ -71,13 +114,34 @@ pub struct JupiterQuote {
     pub slippage_bps: u16,
 }
 
+impl JupiterQuote {
+    // Worst-case output we accept. Only ExactIn quotes let the output float.
+    pub fn min_out_amount(&self) -> u64 {
+        match self.swap_mode {
+            SwapMode::ExactIn => self.other_amount_threshold,
+            SwapMode::ExactOut => self.out_amount,
+        }
+    }
+
+    // Worst-case input we may spend. Only ExactOut quotes let the input float.
+    pub fn max_in_amount(&self) -> u64 {
+        match self.swap_mode {
+            SwapMode::ExactIn => self.in_amount,
+            SwapMode::ExactOut => self.other_amount_threshold,
+        }
+    }
+}
+
+// Route types mirror Jupiter's wire format
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct RoutePlan {
     pub swap_info: SwapInfo,
     pub percent: u8,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SwapInfo {
     pub amm_key: String,
     pub label: String,
@@ -101,7 +165,9 @@ pub struct JupiterSwap {
 pub struct SwapRequest {
     pub input_mint: String,
     pub output_mint: String,
+    // Input amount for ExactIn, desired output amount for ExactOut
     pub amount: u64,
+    pub swap_mode: SwapMode,
     pub user_public_key: String,
     pub slippage: f64,
     pub priority_fee: u64,
@@ -180,6 +246,28 @@ pub enum ExecutionMethod {
     Hybrid,
 }
 
+// How a trade was actually sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionPath {
+    Jito,
+    Rpc,
+}
+
+impl ExecutionPath {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionPath::Jito => "jito",
+            ExecutionPath::Rpc => "rpc",
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutionPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 // Portfolio types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Portfolio {
@@ -199,23 +287,6 @@ pub struct TokenBalance {
     pub price: f64,
 }
 
-// Risk management types
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RiskSettings {
-    pub max_position_size: f64,
-    pub max_daily_loss: f64,
-    pub max_slippage: f64,
-    pub min_profit_threshold: f64,
-    pub max_trades_per_hour: u32,
-    pub enable_stop_loss: bool,
-    pub stop_loss_percentage: f64,
-    pub max_gas_price: u64,
-    pub min_liquidity: f64,
-    pub use_jupiter_for_execution: bool,
-    pub jupiter_slippage_bps: u16,
-    pub max_price_impact_pct: f64,
-}
-
 // Monitoring and statistics types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingStats {
@@ -245,23 +316,162 @@ pub struct PerformanceMetrics {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JupiterConfig {
     pub enabled: bool,
+    // Swap API base serving /quote and /swap. Used after `endpoints` are exhausted.
     pub api_url: String,
+    // Tried in order before api_url, e.g. a self-hosted instance
+    #[serde(default)]
+    pub endpoints: Vec<JupiterEndpointConfig>,
+    // Consecutive failures before an endpoint is skipped for endpoint_cooldown_ms
+    #[serde(default = "default_endpoint_failure_threshold")]
+    pub endpoint_failure_threshold: u32,
+    #[serde(default = "default_endpoint_cooldown_ms")]
+    pub endpoint_cooldown_ms: u64,
+    #[serde(default = "default_price_api_url")]
+    pub price_api_url: String,
+    #[serde(default = "default_tokens_api_url")]
+    pub tokens_api_url: String,
     pub api_key: Option<String>,
     pub timeout_ms: u64,
     pub retry_attempts: u32,
+    // How long to skip a pair after Jupiter reports no route or an untradable token
+    #[serde(default = "default_pair_cooldown_ms")]
+    pub pair_cooldown_ms: u64,
     pub default_slippage_bps: u16,
     pub max_price_impact_pct: f64,
     pub preferred_dexes: Vec<String>,
     pub excluded_dexes: Vec<String>,
+    #[serde(default = "default_max_route_hops")]
+    pub max_route_hops: usize,
+    // Sent as the quote's maxAccounts, so Jupiter only returns routes that fit
+    #[serde(default = "default_max_route_accounts")]
+    pub max_route_accounts: u8,
     pub use_shared_accounts: bool,
     pub dynamic_compute_unit_limit: bool,
     pub prioritization_fee_lamports: u64,
 }
 
+fn default_endpoint_failure_threshold() -> u32 {
+    3
+}
+
+fn default_endpoint_cooldown_ms() -> u64 {
+    30_000
+}
+
+fn default_price_api_url() -> String {
+    "https://lite-api.jup.ag/price/v2".to_string()
+}
+
+fn default_tokens_api_url() -> String {
+    "https://lite-api.jup.ag/tokens/v1".to_string()
+}
+
+fn default_pair_cooldown_ms() -> u64 {
+    60_000
+}
+
+fn default_max_route_hops() -> usize {
+    3
+}
+
+fn default_max_route_accounts() -> u8 {
+    64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JupiterEndpointConfig {
+    pub name: String,
+    pub url: String,
+    pub api_key: Option<String>,
+}
+
 // Error types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JupiterError {
+    NoRouteFound { input_mint: String, output_mint: String },
+    TokenNotTradable { mint: String },
+    SlippageExceeded { slippage_bps: u16 },
+    RateLimited { retry_after_ms: Option<u64> },
+    Unauthorized { status: u16, message: String },
+    Network { message: String },
+    InvalidResponse { message: String },
+    Api { status: u16, code: Option<String>, message: String },
+}
+
+// What the engine should do after a Jupiter failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JupiterErrorAction {
+    Retry,
+    // Drop this opportunity, the pair stays tradable
+    Skip,
+    CooldownPair,
+    Halt,
+}
+
+impl JupiterError {
+    pub fn action(&self) -> JupiterErrorAction {
+        match self {
+            JupiterError::NoRouteFound { .. } | JupiterError::TokenNotTradable { .. } => {
+                JupiterErrorAction::CooldownPair
+            }
+            // The price moved, asking again for the same quote won't help
+            JupiterError::SlippageExceeded { .. } => JupiterErrorAction::Skip,
+            JupiterError::RateLimited { .. }
+            | JupiterError::Network { .. }
+            | JupiterError::InvalidResponse { .. } => JupiterErrorAction::Retry,
+            JupiterError::Unauthorized { .. } => JupiterErrorAction::Halt,
+            JupiterError::Api { status, .. } if *status >= 500 => JupiterErrorAction::Retry,
+            JupiterError::Api { .. } => JupiterErrorAction::CooldownPair,
+        }
+    }
+
+    // Failures specific to the endpoint that served the request, worth retrying elsewhere
+    pub fn is_endpoint_failure(&self) -> bool {
+        match self {
+            JupiterError::RateLimited { .. }
+            | JupiterError::Unauthorized { .. }
+            | JupiterError::Network { .. }
+            | JupiterError::InvalidResponse { .. } => true,
+            JupiterError::Api { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for JupiterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JupiterError::NoRouteFound { input_mint, output_mint } => {
+                write!(f, "No route found for {} -> {}", input_mint, output_mint)
+            }
+            JupiterError::TokenNotTradable { mint } => write!(f, "Token not tradable: {}", mint),
+            JupiterError::SlippageExceeded { slippage_bps } => {
+                write!(f, "Slippage exceeded ({} bps)", slippage_bps)
+            }
+            JupiterError::RateLimited { retry_after_ms: Some(ms) } => {
+                write!(f, "Rate limited, retry after {}ms", ms)
+            }
+            JupiterError::RateLimited { retry_after_ms: None } => write!(f, "Rate limited"),
+            JupiterError::Unauthorized { status, message } => {
+                write!(f, "Unauthorized ({}): {}", status, message)
+            }
+            JupiterError::Network { message } => write!(f, "Network error: {}", message),
+            JupiterError::InvalidResponse { message } => write!(f, "Invalid response: {}", message),
+            JupiterError::Api { status, code: Some(code), message } => {
+                write!(f, "{} ({}): {}", code, status, message)
+            }
+            JupiterError::Api { status, code: None, message } => {
+                write!(f, "HTTP {}: {}", status, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JupiterError {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ArbitrageError {
-    JupiterApiError(String),
+    JupiterApiError(JupiterError),
     DexApiError(String),
     InsufficientLiquidity,
     PriceImpactTooHigh,
@@ -270,6 +480,15 @@ pub enum ArbitrageError {
     RiskCheckFailed,
     TransactionFailed(String),
     NetworkError(String),
+    // The bundle would revert or fall short of the minimum, so no path should send it
+    SimulationRejected(String),
+    // Not confirmed before its blockhash expired, safe to re-sign and resend
+    BlockhashExpired(String),
+    // Unconfirmed at confirm_timeout with its blockhash still valid. It may yet
+    // land, so it must not be re-signed.
+    ConfirmationTimeout(String),
+    // Landed but ran out of compute units, so the route's limit was too low
+    ComputeBudgetExceeded(String),
 }
 
 impl std::fmt::Display for ArbitrageError {
@@ -284,8 +503,18 @@ impl std::fmt::Display for ArbitrageError {
             ArbitrageError::RiskCheckFailed => write!(f, "Risk check failed"),
             ArbitrageError::TransactionFailed(msg) => write!(f, "Transaction failed: {}", msg),
             ArbitrageError::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            ArbitrageError::SimulationRejected(msg) => write!(f, "Simulation rejected: {}", msg),
+            ArbitrageError::BlockhashExpired(msg) => write!(f, "Blockhash expired: {}", msg),
+            ArbitrageError::ConfirmationTimeout(msg) => write!(f, "Confirmation timed out: {}", msg),
+            ArbitrageError::ComputeBudgetExceeded(msg) => write!(f, "Compute budget exceeded: {}", msg),
         }
     }
 }
 
 impl std::error::Error for ArbitrageError {}
+
+impl From<JupiterError> for ArbitrageError {
+    fn from(err: JupiterError) -> Self {
+        ArbitrageError::JupiterApiError(err)
+    }
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use solana_sdk::{pubkey, pubkey::Pubkey, signature::Keypair};
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+// Accepts either a base58 secret key (wallet export) or the JSON byte array
+// written by solana-keygen
+pub fn parse_keypair(private_key: &str) -> Result<Keypair> {
+    let private_key = private_key.trim();
+    if private_key.is_empty() {
+        return Err(anyhow::anyhow!("Wallet private key is not configured"));
+    }
+
+    let bytes = if private_key.starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(private_key)?
+    } else {
+        bs58::decode(private_key).into_vec()?
+    };
+
+    Keypair::try_from(bytes.as_slice()).map_err(|e| anyhow::anyhow!("Invalid private key: {}", e))
+}
+
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+// SPL token account layout: mint (32), owner (32), amount (u64 LE), ...
+pub fn token_account_amount(data: &[u8]) -> Option<u64> {
+    data.get(64..72).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
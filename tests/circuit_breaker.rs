@@ -0,0 +1,54 @@
+// Consecutive failure counting in CircuitBreaker: which execution outcomes
+// count against the streak and which leave it alone
+use solana_jupiter_arbitrage_bot::circuit_breaker::{CircuitBreaker, ExecutionOutcome, TripReason};
+use solana_jupiter_arbitrage_bot::config::CircuitBreakerConfig;
+
+fn breaker(max_consecutive_failures: u32) -> CircuitBreaker {
+    CircuitBreaker::new(CircuitBreakerConfig {
+        max_consecutive_failures,
+        ..CircuitBreakerConfig::default()
+    })
+}
+
+#[tokio::test]
+async fn trades_that_never_landed_do_not_trip() {
+    let breaker = breaker(2);
+    for _ in 0..10 {
+        breaker.record_trade(ExecutionOutcome::NotLanded, None).await;
+    }
+    assert!(!breaker.is_tripped().await);
+}
+
+#[tokio::test]
+async fn reverts_and_partial_fills_trip() {
+    let breaker = breaker(3);
+    breaker.record_trade(ExecutionOutcome::Reverted, None).await;
+    breaker.record_trade(ExecutionOutcome::PartialFill, None).await;
+    // Drops in between neither count nor break the streak
+    breaker.record_trade(ExecutionOutcome::NotLanded, None).await;
+    assert!(!breaker.is_tripped().await);
+
+    breaker.record_trade(ExecutionOutcome::Reverted, None).await;
+    let trip = breaker.trip_status().await.unwrap();
+    assert_eq!(trip.reason, TripReason::ConsecutiveFailures { count: 3 });
+}
+
+#[tokio::test]
+async fn a_fill_resets_the_streak() {
+    let breaker = breaker(2);
+    breaker.record_trade(ExecutionOutcome::Reverted, None).await;
+    breaker.record_trade(ExecutionOutcome::Filled, Some(0.1)).await;
+    breaker.record_trade(ExecutionOutcome::Reverted, None).await;
+    assert!(!breaker.is_tripped().await);
+}
+
+#[tokio::test]
+async fn no_rpc_endpoint_in_rotation_trips() {
+    let breaker = breaker(3);
+    breaker.check_slot_lag(Some(0)).await;
+    assert!(!breaker.is_tripped().await);
+
+    breaker.check_slot_lag(None).await;
+    let trip = breaker.trip_status().await.unwrap();
+    assert_eq!(trip.reason, TripReason::RpcUnavailable);
+}
@@ -0,0 +1,110 @@
+// Local HTTP stand-ins for the external APIs, shared by the integration tests.
+// Each test crate uses only part of it.
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub struct Request {
+    // Request line, e.g. "POST /api/v1/bundles HTTP/1.1"
+    pub line: String,
+    pub body: Vec<u8>,
+}
+
+// Answers every request with the status and body `respond` returns. Returns
+// the base URL.
+pub async fn serve<F>(respond: F) -> String
+where
+    F: Fn(&Request) -> (u16, String) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let respond = Arc::new(respond);
+
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let respond = respond.clone();
+            tokio::spawn(async move {
+                let Some(request) = read_request(&mut stream).await else {
+                    return;
+                };
+                let (status, body) = respond(&request);
+                let response = format!(
+                    "HTTP/1.1 {} Stand-in\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    url
+}
+
+// JSON-RPC over `serve`: answers each call with `result(method, params)`.
+// Returns the base URL and every request received, with its request line.
+pub async fn json_rpc<F>(result: F) -> (String, Arc<Mutex<Vec<(String, Value)>>>)
+where
+    F: Fn(&str, &Value) -> Value + Send + Sync + 'static,
+{
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let recorded = calls.clone();
+    let url = serve(move |request| {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        let method = body["method"].as_str().unwrap_or_default().to_string();
+        let result = result(&method, &body["params"]);
+        recorded.lock().unwrap().push((request.line.clone(), body));
+        (200, json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string())
+    })
+    .await;
+    (url, calls)
+}
+
+// Methods called so far, in order
+pub fn methods(calls: &Arc<Mutex<Vec<(String, Value)>>>) -> Vec<String> {
+    calls
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, body)| body["method"].as_str().unwrap_or_default().to_string())
+        .collect()
+}
+
+// Reads the headers, then Content-Length bytes of body if there is one
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    let header_end = loop {
+        if let Some(at) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break at + 4;
+        }
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    };
+
+    let headers = String::from_utf8_lossy(&request[..header_end]).to_string();
+    let line = headers.lines().next().unwrap_or_default().to_string();
+    let length: usize = headers
+        .to_lowercase()
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0);
+    while request.len() < header_end + length {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+    Some(Request {
+        line,
+        body: request[header_end..header_end + length].to_vec(),
+    })
+}
@@ -0,0 +1,87 @@
+// sendBundle in JitoClient against a local JSON-RPC stand-in for the block engine
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+use solana_jupiter_arbitrage_bot::config::{Config, JitoConfig};
+use solana_jupiter_arbitrage_bot::jito_client::JitoClient;
+use solana_sdk::{
+    hash::Hash,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::VersionedTransaction,
+};
+use solana_system_interface::instruction as system_instruction;
+
+const BUNDLE_ID: &str = "3f6a9d2c1b0e";
+
+fn config(url: &str) -> JitoConfig {
+    JitoConfig {
+        bundle_endpoint: url.to_string(),
+        block_engines: Vec::new(),
+        simulation_rpc_url: String::new(),
+        ..Config::default().jito
+    }
+}
+
+fn leg(payer: &Keypair) -> VersionedTransaction {
+    let instruction = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+    let message = v0::Message::try_compile(&payer.pubkey(), &[instruction], &[], Hash::new_unique()).unwrap();
+    VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer]).unwrap()
+}
+
+fn decode(encoded: &Value) -> VersionedTransaction {
+    bincode::deserialize(&STANDARD.decode(encoded.as_str().unwrap()).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn send_bundle_posts_the_signed_legs_and_tip() {
+    let tip_account = Pubkey::new_unique();
+    let (url, calls) = common::json_rpc(move |method, _| match method {
+        "getTipAccounts" => json!([tip_account.to_string()]),
+        "sendBundle" => json!(BUNDLE_ID),
+        _ => Value::Null,
+    })
+    .await;
+    let client = JitoClient::new(config(&url));
+    let payer = Keypair::new();
+    let leg = leg(&payer);
+
+    let bundle_id = client.submit_bundle(&payer, vec![leg.clone()], 10_000, None).await.unwrap();
+    assert_eq!(bundle_id, BUNDLE_ID);
+    assert_eq!(common::methods(&calls), ["getTipAccounts", "sendBundle"]);
+
+    let (line, request) = calls.lock().unwrap()[1].clone();
+    assert!(line.starts_with("POST /api/v1/bundles "), "{}", line);
+    assert_eq!(request["jsonrpc"], "2.0");
+    assert_eq!(request["params"][1], json!({ "encoding": "base64" }));
+
+    // The leg as given, then the tip, both signed by the payer
+    let sent = request["params"][0].as_array().unwrap();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(decode(&sent[0]).signatures, leg.signatures);
+    let tip = decode(&sent[1]);
+    assert!(tip.verify_with_results().iter().all(|ok| *ok));
+    assert_eq!(tip.message.static_account_keys()[0], payer.pubkey());
+    assert!(tip.message.static_account_keys().contains(&tip_account));
+    assert_eq!(tip.message.recent_blockhash(), leg.message.recent_blockhash());
+}
+
+#[tokio::test]
+async fn send_bundle_surfaces_json_rpc_errors() {
+    let url = common::serve(|request| {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        let response = match body["method"].as_str() {
+            Some("getTipAccounts") => json!({ "jsonrpc": "2.0", "id": 1, "result": [Pubkey::new_unique().to_string()] }),
+            _ => json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32602, "message": "bundle contains an expired blockhash" } }),
+        };
+        (200, response.to_string())
+    })
+    .await;
+    let client = JitoClient::new(config(&url));
+    let payer = Keypair::new();
+
+    let err = client.submit_bundle(&payer, vec![leg(&payer)], 10_000, None).await.unwrap_err();
+    assert!(err.to_string().contains("expired blockhash"), "{}", err);
+}
@@ -0,0 +1,131 @@
+// simulateBundle guard in submit_bundle against a local JSON-RPC stand-in for
+// the block engine and the simulation RPC
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+use solana_jupiter_arbitrage_bot::config::{Config, JitoConfig};
+use solana_jupiter_arbitrage_bot::jito_client::{JitoClient, SimulationGuard};
+use solana_jupiter_arbitrage_bot::types::ArbitrageError;
+use solana_sdk::{
+    hash::Hash,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::VersionedTransaction,
+};
+use solana_system_interface::instruction as system_instruction;
+use std::sync::{Arc, Mutex};
+
+type Calls = Arc<Mutex<Vec<(String, Value)>>>;
+
+const BUNDLE_ID: &str = "b9e0c1d2a3f4";
+
+// Answers simulateBundle with `simulation` and the rest with fixed results
+async fn stand_in(simulation: Value) -> (String, Calls) {
+    common::json_rpc(move |method, _| match method {
+        "getTipAccounts" => json!([Pubkey::new_unique().to_string()]),
+        "simulateBundle" => json!({ "context": { "slot": 1 }, "value": simulation }),
+        "sendBundle" => json!(BUNDLE_ID),
+        _ => Value::Null,
+    })
+    .await
+}
+
+fn config(url: &str, simulate: bool) -> JitoConfig {
+    JitoConfig {
+        bundle_endpoint: url.to_string(),
+        block_engines: Vec::new(),
+        simulation_rpc_url: if simulate { url.to_string() } else { String::new() },
+        ..Config::default().jito
+    }
+}
+
+// A simulated account holding `lamports`, with SPL token account data for `amount`
+fn account(lamports: u64, amount: u64) -> Value {
+    let mut data = vec![0u8; 165];
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    json!({ "lamports": lamports, "data": [STANDARD.encode(data), "base64"] })
+}
+
+// One swap leg and the tip, moving the token account from `before` to `after`
+fn simulation(summary: Value, before: u64, after: u64) -> Value {
+    json!({
+        "summary": summary,
+        "transactionResults": [
+            {
+                "err": null,
+                "preExecutionAccounts": [account(1_000_000_000, 0), account(2_039_280, before)],
+                "postExecutionAccounts": null,
+                "unitsConsumed": 120_000,
+            },
+            {
+                "err": null,
+                "preExecutionAccounts": null,
+                "postExecutionAccounts": [account(999_000_000, 0), account(2_039_280, after)],
+                "unitsConsumed": 150,
+            },
+        ],
+    })
+}
+
+fn leg(payer: &Keypair) -> VersionedTransaction {
+    let instruction = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+    let message = v0::Message::try_compile(&payer.pubkey(), &[instruction], &[], Hash::new_unique()).unwrap();
+    VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer]).unwrap()
+}
+
+fn guard(min_net: i128) -> SimulationGuard {
+    SimulationGuard {
+        token_account: Pubkey::new_unique(),
+        native: false,
+        min_net,
+    }
+}
+
+#[tokio::test]
+async fn passing_simulation_sends_the_bundle() {
+    let (url, calls) = stand_in(simulation(json!("succeeded"), 5_000, 8_000)).await;
+    let client = JitoClient::new(config(&url, true));
+    let payer = Keypair::new();
+
+    let bundle_id = client.submit_bundle(&payer, vec![leg(&payer)], 100_000, Some(&guard(2_500))).await.unwrap();
+    assert_eq!(bundle_id, BUNDLE_ID);
+    assert_eq!(common::methods(&calls), ["getTipAccounts", "simulateBundle", "sendBundle"]);
+}
+
+#[tokio::test]
+async fn reverted_simulation_is_rejected_without_sending() {
+    let failed = json!({ "failed": { "error": { "TransactionFailure": [[0], "custom program error: 0x1771"] }, "tx_signature": null } });
+    let (url, calls) = stand_in(simulation(failed, 5_000, 5_000)).await;
+    let client = JitoClient::new(config(&url, true));
+    let payer = Keypair::new();
+
+    let err = client.submit_bundle(&payer, vec![leg(&payer)], 100_000, Some(&guard(0))).await.unwrap_err();
+    match err.downcast_ref::<ArbitrageError>() {
+        Some(ArbitrageError::SimulationRejected(reason)) => assert!(reason.contains("0x1771"), "{}", reason),
+        other => panic!("expected a simulation rejection, got {:?}", other),
+    }
+    assert_eq!(common::methods(&calls), ["getTipAccounts", "simulateBundle"]);
+}
+
+#[tokio::test]
+async fn simulation_below_the_minimum_is_rejected_without_sending() {
+    let (url, calls) = stand_in(simulation(json!("succeeded"), 5_000, 6_000)).await;
+    let client = JitoClient::new(config(&url, true));
+    let payer = Keypair::new();
+
+    let err = client.submit_bundle(&payer, vec![leg(&payer)], 100_000, Some(&guard(2_500))).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<ArbitrageError>(), Some(ArbitrageError::SimulationRejected(_))));
+    assert_eq!(common::methods(&calls), ["getTipAccounts", "simulateBundle"]);
+}
+
+#[tokio::test]
+async fn bundle_is_sent_unsimulated_without_a_simulation_rpc() {
+    let (url, calls) = stand_in(simulation(json!("succeeded"), 0, 0)).await;
+    let client = JitoClient::new(config(&url, false));
+    let payer = Keypair::new();
+
+    client.submit_bundle(&payer, vec![leg(&payer)], 100_000, Some(&guard(2_500))).await.unwrap();
+    assert_eq!(common::methods(&calls), ["getTipAccounts", "sendBundle"]);
+}
@@ -0,0 +1,166 @@
+// Contract tests against Jupiter response fixtures in tests/fixtures/jupiter
+use solana_jupiter_arbitrage_bot::jupiter_client::{
+    classify_error, ErrorContext, JupiterQuoteRequest, JupiterQuoteResponse, JupiterSwapRequest,
+    JupiterSwapInstructions, JupiterSwapResponse, PriceResponse, TokenInfo,
+};
+use solana_jupiter_arbitrage_bot::types::{JupiterError, JupiterQuote, SwapMode};
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/jupiter/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("missing fixture {}: {}", path, e))
+}
+
+#[test]
+fn exact_in_quote_deserializes_and_converts() {
+    let response: JupiterQuoteResponse = serde_json::from_str(&fixture("quote_exact_in.json")).unwrap();
+    let quote = JupiterQuote::try_from(response).unwrap();
+
+    assert_eq!(quote.input_mint, SOL);
+    assert_eq!(quote.output_mint, USDC);
+    assert_eq!(quote.swap_mode, SwapMode::ExactIn);
+    assert_eq!(quote.in_amount, 1_000_000_000);
+    assert_eq!(quote.out_amount, 148_213_957);
+    assert_eq!(quote.min_out_amount(), 147_472_887);
+    assert_eq!(quote.context_slot, 331_452_817);
+    assert_eq!(quote.route_plan.len(), 2);
+    assert_eq!(quote.route_plan[0].swap_info.label, "Whirlpool");
+    assert_eq!(quote.route_plan[0].percent, 70);
+    assert_eq!(quote.route_plan[1].swap_info.fee_amount, "750000");
+}
+
+#[test]
+fn exact_out_quote_caps_input() {
+    let response: JupiterQuoteResponse = serde_json::from_str(&fixture("quote_exact_out.json")).unwrap();
+    let quote = JupiterQuote::try_from(response).unwrap();
+
+    assert_eq!(quote.swap_mode, SwapMode::ExactOut);
+    assert_eq!(quote.out_amount, 1_000_000_000);
+    assert_eq!(quote.min_out_amount(), 1_000_000_000);
+    assert_eq!(quote.max_in_amount(), 149_133_217);
+}
+
+#[test]
+fn quote_round_trips_into_swap_request() {
+    let response: JupiterQuoteResponse = serde_json::from_str(&fixture("quote_exact_in.json")).unwrap();
+    let quote = JupiterQuote::try_from(response).unwrap();
+
+    let request = JupiterSwapRequest {
+        quote_response: JupiterQuoteResponse::from(&quote),
+        user_public_key: SOL.to_string(),
+        wrap_and_unwrap_sol: Some(true),
+        dynamic_compute_unit_limit: Some(true),
+        prioritization_fee_lamports: None,
+        as_legacy_transaction: None,
+        use_shared_accounts: None,
+        fee_account: None,
+        tracking_account: None,
+        compute_unit_price_micro_lamports: None,
+    };
+    let body = serde_json::to_value(&request).unwrap();
+
+    assert_eq!(body["userPublicKey"], SOL);
+    assert_eq!(body["dynamicComputeUnitLimit"], true);
+    assert!(body.get("prioritizationFeeLamports").is_none());
+    assert_eq!(body["quoteResponse"]["otherAmountThreshold"], "147472887");
+    assert_eq!(body["quoteResponse"]["swapMode"], "ExactIn");
+    assert_eq!(body["quoteResponse"]["routePlan"][0]["swapInfo"]["ammKey"],
+               "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE");
+}
+
+#[test]
+fn quote_request_uses_camel_case_query() {
+    let request = JupiterQuoteRequest {
+        input_mint: SOL.to_string(),
+        output_mint: USDC.to_string(),
+        amount: 1_000_000_000,
+        slippage_bps: 50,
+        swap_mode: Some(SwapMode::ExactOut),
+        dexes: Some(vec!["Raydium".to_string(), "Whirlpool".to_string()]),
+        exclude_dexes: None,
+        platform_fee_bps: None,
+        max_accounts: Some(64),
+    };
+    let built = reqwest::Client::new()
+        .get("http://localhost/swap/v1/quote")
+        .query(&request)
+        .build()
+        .unwrap();
+    let query = built.url().query().unwrap().to_string();
+
+    assert!(query.contains(&format!("inputMint={}", SOL)));
+    assert!(query.contains(&format!("outputMint={}", USDC)));
+    assert!(query.contains("slippageBps=50"));
+    assert!(query.contains("swapMode=ExactOut"));
+    assert!(query.contains("dexes=Raydium%2CWhirlpool"));
+    assert!(query.contains("maxAccounts=64"));
+    assert!(!query.contains("excludeDexes"));
+}
+
+#[test]
+fn swap_response_deserializes() {
+    let response: JupiterSwapResponse = serde_json::from_str(&fixture("swap.json")).unwrap();
+
+    assert_eq!(response.last_valid_block_height, 309_764_822);
+    assert_eq!(response.prioritization_fee_lamports, 100_000);
+    assert_eq!(response.compute_unit_limit, 186_532);
+    assert!(response.simulation_error.is_none());
+}
+
+#[test]
+fn swap_instructions_response_deserializes() {
+    let response: JupiterSwapInstructions =
+        serde_json::from_str(&fixture("swap_instructions.json")).unwrap();
+
+    assert!(response.token_ledger_instruction.is_none());
+    assert_eq!(response.compute_budget_instructions.len(), 2);
+    assert_eq!(response.setup_instructions.len(), 3);
+    assert_eq!(response.setup_instructions[0].program_id, "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+    assert_eq!(response.swap_instruction.program_id, "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+    assert!(response.swap_instruction.accounts[1].is_signer);
+    assert!(response.cleanup_instruction.is_some());
+    assert_eq!(response.address_lookup_table_addresses.len(), 1);
+    assert_eq!(response.compute_unit_limit, 186_532);
+}
+
+#[test]
+fn price_response_skips_unknown_mints() {
+    let response: PriceResponse = serde_json::from_str(&fixture("price_v2.json")).unwrap();
+
+    assert_eq!(response.data.len(), 3);
+    let sol = response.data[SOL].as_ref().unwrap();
+    assert_eq!(sol.price.parse::<f64>().unwrap(), 148.213957);
+    assert!(response.data["UnknownMint1111111111111111111111111111111"].is_none());
+}
+
+#[test]
+fn token_list_deserializes() {
+    let tokens: Vec<TokenInfo> = serde_json::from_str(&fixture("tokens_tagged_verified.json")).unwrap();
+
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0].symbol, "SOL");
+    assert_eq!(tokens[0].decimals, 9);
+    assert!(tokens[0].logo_uri.is_some());
+    assert!(tokens[0].freeze_authority.is_none());
+    assert_eq!(tokens[1].freeze_authority.as_deref(), Some("7dGbd2QZcCKcTndnHcTL8q7SMVXAkp688NTQYwrRCrar"));
+}
+
+#[test]
+fn error_body_classifies_no_route() {
+    let context = ErrorContext {
+        input_mint: SOL,
+        output_mint: USDC,
+        slippage_bps: 50,
+    };
+    let err = classify_error(400, None, &fixture("error_no_route.json"), &context);
+
+    match err {
+        JupiterError::NoRouteFound { input_mint, output_mint } => {
+            assert_eq!(input_mint, SOL);
+            assert_eq!(output_mint, USDC);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
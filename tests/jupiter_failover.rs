@@ -0,0 +1,130 @@
+// Endpoint failover and cooldown against local HTTP stand-ins for the Jupiter API
+mod common;
+
+use solana_jupiter_arbitrage_bot::jupiter_client::{JupiterClient, JupiterQuoteRequest};
+use solana_jupiter_arbitrage_bot::types::{JupiterConfig, JupiterEndpointConfig, JupiterError, SwapMode};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+const SOL: &str = "So11111111111111111111111111111111111111112";
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/jupiter/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("missing fixture {}: {}", path, e))
+}
+
+// Answers every request with `status` and `body`, counting requests. Returns
+// the base URL and the counter.
+async fn stand_in(status: u16, body: String) -> (String, Arc<AtomicUsize>) {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let url = common::serve(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        (status, body.clone())
+    })
+    .await;
+    (url, hits)
+}
+
+fn config(primary: &str, fallback: &str, cooldown_ms: u64) -> JupiterConfig {
+    JupiterConfig {
+        enabled: true,
+        api_url: fallback.to_string(),
+        endpoints: vec![JupiterEndpointConfig {
+            name: "primary".to_string(),
+            url: primary.to_string(),
+            api_key: None,
+        }],
+        endpoint_failure_threshold: 2,
+        endpoint_cooldown_ms: cooldown_ms,
+        price_api_url: fallback.to_string(),
+        tokens_api_url: fallback.to_string(),
+        api_key: None,
+        timeout_ms: 2_000,
+        retry_attempts: 1,
+        pair_cooldown_ms: 60_000,
+        default_slippage_bps: 50,
+        max_price_impact_pct: 5.0,
+        preferred_dexes: Vec::new(),
+        excluded_dexes: Vec::new(),
+        max_route_hops: 3,
+        max_route_accounts: 64,
+        use_shared_accounts: true,
+        dynamic_compute_unit_limit: true,
+        prioritization_fee_lamports: 0,
+    }
+}
+
+fn quote_request() -> JupiterQuoteRequest {
+    JupiterQuoteRequest {
+        input_mint: SOL.to_string(),
+        output_mint: USDC.to_string(),
+        amount: 1_000_000_000,
+        slippage_bps: 50,
+        swap_mode: Some(SwapMode::ExactIn),
+        dexes: None,
+        exclude_dexes: None,
+        platform_fee_bps: None,
+        max_accounts: Some(64),
+    }
+}
+
+#[tokio::test]
+async fn failing_endpoint_fails_over_then_cools_down() {
+    let (primary, primary_hits) = stand_in(500, r#"{"error":"internal"}"#.to_string()).await;
+    let (fallback, fallback_hits) = stand_in(200, fixture("quote_exact_in.json")).await;
+    let client = JupiterClient::new(&config(&primary, &fallback, 300));
+
+    // Below the failure threshold the primary is still tried first
+    for expected in 1..=2 {
+        let quote = client.get_quote(quote_request()).await.unwrap();
+        assert_eq!(quote.out_amount, 148_213_957);
+        assert_eq!(primary_hits.load(Ordering::SeqCst), expected);
+        assert_eq!(fallback_hits.load(Ordering::SeqCst), expected);
+    }
+
+    // Two failures in a row put it on cooldown, so it's skipped
+    let statuses = client.endpoint_statuses().await;
+    assert_eq!(statuses[0].name, "primary");
+    assert!(!statuses[0].healthy);
+    assert!(statuses[1].healthy);
+    client.get_quote(quote_request()).await.unwrap();
+    assert_eq!(primary_hits.load(Ordering::SeqCst), 2);
+    assert_eq!(fallback_hits.load(Ordering::SeqCst), 3);
+
+    // Once the cooldown passes it gets another chance
+    tokio::time::sleep(Duration::from_millis(350)).await;
+    client.get_quote(quote_request()).await.unwrap();
+    assert_eq!(primary_hits.load(Ordering::SeqCst), 3);
+    assert_eq!(fallback_hits.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn request_errors_do_not_fail_over() {
+    let (primary, primary_hits) = stand_in(400, fixture("error_no_route.json")).await;
+    let (fallback, fallback_hits) = stand_in(200, fixture("quote_exact_in.json")).await;
+    let client = JupiterClient::new(&config(&primary, &fallback, 300));
+
+    let err = client.get_quote(quote_request()).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<JupiterError>(), Some(JupiterError::NoRouteFound { .. })));
+    assert_eq!(primary_hits.load(Ordering::SeqCst), 1);
+    assert_eq!(fallback_hits.load(Ordering::SeqCst), 0);
+    assert!(client.endpoint_statuses().await[0].healthy);
+}
+
+#[tokio::test]
+async fn unreachable_endpoint_fails_over() {
+    // Bound and dropped, so connections to it are refused
+    let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let primary = format!("http://{}", closed.local_addr().unwrap());
+    drop(closed);
+    let (fallback, fallback_hits) = stand_in(200, fixture("quote_exact_in.json")).await;
+    let client = JupiterClient::new(&config(&primary, &fallback, 300));
+
+    client.get_quote(quote_request()).await.unwrap();
+    assert_eq!(fallback_hits.load(Ordering::SeqCst), 1);
+    assert_eq!(client.endpoint_statuses().await[0].health.failures, 1);
+}
@@ -0,0 +1,242 @@
+// Pre-trade limits in RiskManager: the rolling hourly trade window, the UTC
+// day rollover of realized P&L, open trade exposure, stranded mints, and what
+// each rejection reports
+use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use solana_jupiter_arbitrage_bot::config::{Config, ExposureLimitsConfig, RiskSettings, TokenScreeningConfig};
+use solana_jupiter_arbitrage_bot::risk_manager::{ExposureScope, RiskManager, RiskRejection, TradeExposure};
+use solana_jupiter_arbitrage_bot::token_screen::{MintProfile, MintRisk};
+use solana_jupiter_arbitrage_bot::types::TradeRequest;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+fn settings() -> RiskSettings {
+    RiskSettings {
+        max_position_size: 1_000.0,
+        max_daily_loss: 100.0,
+        max_slippage: 1.0,
+        max_trades_per_hour: 3,
+        ..Config::default().risk_settings
+    }
+}
+
+fn manager(token_screening: TokenScreeningConfig) -> RiskManager {
+    RiskManager::new(settings(), token_screening, ExposureLimitsConfig::default())
+}
+
+fn request(amount: f64, max_slippage: f64) -> TradeRequest {
+    TradeRequest {
+        opportunity_id: "opportunity".to_string(),
+        amount,
+        private_key: String::new(),
+        max_slippage,
+        priority_fee: 0,
+        use_jito: false,
+        jito_tip: 0,
+    }
+}
+
+fn profile(mint: Pubkey) -> MintProfile {
+    MintProfile {
+        mint,
+        token_2022: false,
+        decimals: 6,
+        freeze_authority: None,
+        transfer_fee_bps: None,
+        permanent_delegate: None,
+    }
+}
+
+fn check(manager: &RiskManager, request: &TradeRequest, mints: &[MintProfile]) -> Result<(), RiskRejection> {
+    manager.can_execute_trade(request, mints, &TradeExposure::default())
+}
+
+#[test]
+fn hourly_window_rolls_over() {
+    let mut manager = manager(TokenScreeningConfig::default());
+    let start = Instant::now();
+    manager.record_trade_at(start);
+    manager.record_trade_at(start + Duration::from_secs(30 * 60));
+    manager.record_trade_at(start + Duration::from_secs(45 * 60));
+    assert_eq!(manager.trades_last_hour_at(start + Duration::from_secs(59 * 60)), 3);
+
+    // The first trade ages out an hour after it started, the others later
+    assert_eq!(manager.trades_last_hour_at(start + Duration::from_secs(60 * 60)), 2);
+    assert_eq!(manager.trades_last_hour_at(start + Duration::from_secs(90 * 60)), 1);
+    assert_eq!(manager.trades_last_hour_at(start + Duration::from_secs(105 * 60)), 0);
+
+    // Recording prunes the expired trades
+    manager.record_trade_at(start + Duration::from_secs(100 * 60));
+    assert_eq!(manager.trades_last_hour_at(start + Duration::from_secs(100 * 60)), 2);
+}
+
+#[test]
+fn hourly_limit_rejects_once_reached() {
+    let mut manager = manager(TokenScreeningConfig::default());
+    for _ in 0..2 {
+        manager.record_trade();
+    }
+    assert_eq!(check(&manager, &request(10.0, 0.5), &[]), Ok(()));
+
+    manager.record_trade();
+    assert_eq!(
+        check(&manager, &request(10.0, 0.5), &[]),
+        Err(RiskRejection::HourlyTradeLimit { trades: 3, limit: 3 })
+    );
+}
+
+#[test]
+fn daily_pnl_resets_at_utc_midnight() {
+    let mut manager = manager(TokenScreeningConfig::default());
+    let day = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+    let next_day = day.succ_opt().unwrap();
+
+    manager.record_realized_pnl_on(-60.0, day);
+    manager.record_realized_pnl_on(15.0, day);
+    assert_eq!(manager.daily_pnl_on(day), -45.0);
+    assert_eq!(manager.daily_pnl_on(next_day), 0.0);
+
+    manager.record_realized_pnl_on(-5.0, next_day);
+    assert_eq!(manager.daily_pnl_on(next_day), -5.0);
+    assert_eq!(manager.daily_pnl_on(day), 0.0);
+}
+
+#[test]
+fn daily_loss_limit_rejects_until_the_next_day() {
+    let mut manager = manager(TokenScreeningConfig::default());
+    let today = Utc::now().date_naive();
+
+    // Yesterday's losses don't count against today
+    manager.record_realized_pnl_on(-500.0, today - ChronoDuration::days(1));
+    assert_eq!(check(&manager, &request(10.0, 0.5), &[]), Ok(()));
+
+    manager.record_realized_pnl(-99.0);
+    assert_eq!(check(&manager, &request(10.0, 0.5), &[]), Ok(()));
+    manager.record_realized_pnl(-1.0);
+    assert_eq!(
+        check(&manager, &request(10.0, 0.5), &[]),
+        Err(RiskRejection::DailyLossLimit { realized_loss: 100.0, limit: 100.0 })
+    );
+}
+
+#[test]
+fn oversized_and_high_slippage_trades_are_rejected() {
+    let manager = manager(TokenScreeningConfig::default());
+
+    let rejection = check(&manager, &request(1_500.0, 0.5), &[]).unwrap_err();
+    assert_eq!(rejection, RiskRejection::PositionTooLarge { amount: 1_500.0, limit: 1_000.0 });
+    assert_eq!(rejection.reason(), "position_size");
+
+    let rejection = check(&manager, &request(10.0, 2.5), &[]).unwrap_err();
+    assert_eq!(rejection, RiskRejection::SlippageTooHigh { slippage: 2.5, limit: 1.0 });
+    assert_eq!(rejection.reason(), "slippage");
+    assert_eq!(rejection.to_string(), "slippage 2.50% exceeds the 1.00% limit");
+}
+
+#[test]
+fn mint_screening_rejections() {
+    let denied = Pubkey::new_unique();
+    let allowed = Pubkey::new_unique();
+    let manager = manager(TokenScreeningConfig {
+        denylist: vec![denied.to_string()],
+        allowlist: vec![allowed.to_string()],
+        ..TokenScreeningConfig::default()
+    });
+
+    let rejection = check(&manager, &request(10.0, 0.5), &[profile(denied)]).unwrap_err();
+    assert_eq!(rejection, RiskRejection::TokenDenied { mint: denied.to_string() });
+    assert_eq!(rejection.reason(), "token_denied");
+
+    let authority = Pubkey::new_unique();
+    let frozen = MintProfile { freeze_authority: Some(authority), ..profile(Pubkey::new_unique()) };
+    let rejection = check(&manager, &request(10.0, 0.5), std::slice::from_ref(&frozen)).unwrap_err();
+    assert_eq!(
+        rejection,
+        RiskRejection::RiskyMint { mint: frozen.mint.to_string(), risk: MintRisk::FreezeAuthority(authority) }
+    );
+    assert_eq!(rejection.reason(), "freeze_authority");
+
+    let taxed = MintProfile { token_2022: true, transfer_fee_bps: Some(100), ..profile(Pubkey::new_unique()) };
+    let rejection = check(&manager, &request(10.0, 0.5), &[taxed]).unwrap_err();
+    assert_eq!(rejection.reason(), "transfer_fee");
+
+    // Allowlisted mints skip the account checks
+    let trusted = MintProfile { freeze_authority: Some(authority), ..profile(allowed) };
+    assert_eq!(check(&manager, &request(10.0, 0.5), &[trusted]), Ok(()));
+}
+
+#[test]
+fn open_trade_exposure_is_released_on_drop() {
+    // The default mint limit is half of the default 10,000 USD of capital
+    let mut manager = manager(TokenScreeningConfig::default());
+    let mint = Pubkey::new_unique().to_string();
+    let exposure = |notional: f64| TradeExposure {
+        notional,
+        mints: vec![mint.clone()],
+        ..TradeExposure::default()
+    };
+
+    let open_trade = manager.open_trade(exposure(4_000.0));
+    assert_eq!(
+        manager.can_execute_trade(&request(10.0, 0.5), &[], &exposure(2_000.0)),
+        Err(RiskRejection::ExposureTooHigh {
+            scope: ExposureScope::Mint,
+            key: mint.clone(),
+            exposure: 6_000.0,
+            limit: 5_000.0,
+        })
+    );
+
+    drop(open_trade);
+    assert_eq!(manager.can_execute_trade(&request(10.0, 0.5), &[], &exposure(2_000.0)), Ok(()));
+}
+
+#[test]
+fn stranded_mints_are_blocked_until_unwound() {
+    let mut manager = manager(TokenScreeningConfig::default());
+    let stranded = Pubkey::new_unique().to_string();
+    let exposure = TradeExposure {
+        notional: 10.0,
+        mints: vec![stranded.clone()],
+        ..TradeExposure::default()
+    };
+
+    manager.set_stranded_mints(HashSet::from([stranded.clone()]));
+    let rejection = manager.can_execute_trade(&request(10.0, 0.5), &[], &exposure).unwrap_err();
+    assert_eq!(rejection, RiskRejection::StrandedMint { mint: stranded.clone() });
+    assert_eq!(rejection.reason(), "stranded");
+
+    // Other mints still trade
+    let other = TradeExposure { mints: vec![Pubkey::new_unique().to_string()], ..exposure.clone() };
+    assert_eq!(manager.can_execute_trade(&request(10.0, 0.5), &[], &other), Ok(()));
+
+    manager.set_stranded_mints(HashSet::new());
+    assert_eq!(manager.can_execute_trade(&request(10.0, 0.5), &[], &exposure), Ok(()));
+}
+
+#[test]
+fn pnl_is_booked_at_the_last_known_prices() {
+    let mut manager = manager(TokenScreeningConfig::default());
+    let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    let sol = "So11111111111111111111111111111111111111112";
+
+    manager.record_prices(&[(usdc.to_string(), 1.0), (sol.to_string(), 150.0)].into());
+    manager.record_prices(&[(sol.to_string(), 200.0)].into());
+    assert_eq!(manager.record_realized_pnl_in(&[(usdc, -10.0), (sol, -0.01)]), Ok(-12.0));
+    // Fees of zero don't need a price
+    assert_eq!(manager.record_realized_pnl_in(&[(usdc, 4.0), ("unpriced", 0.0)]), Ok(4.0));
+    assert_eq!(manager.daily_pnl(), -8.0);
+}
+
+#[test]
+fn unpriced_pnl_books_nothing() {
+    let mut manager = manager(TokenScreeningConfig::default());
+    let sol = "So11111111111111111111111111111111111111112";
+    manager.record_prices(&[(sol.to_string(), 150.0)].into());
+
+    assert_eq!(
+        manager.record_realized_pnl_in(&[("unpriced", -1_000_000.0), (sol, -0.01)]),
+        Err("unpriced".to_string())
+    );
+    assert_eq!(manager.daily_pnl(), 0.0);
+}
@@ -0,0 +1,64 @@
+// Slot lag as RpcPool reports it to the circuit breaker, against JSON-RPC
+// stand-ins and an endpoint nothing listens on
+mod common;
+
+use serde_json::{json, Value};
+use solana_jupiter_arbitrage_bot::config::RpcConfig;
+use solana_jupiter_arbitrage_bot::rpc_pool::RpcPool;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+// A node at `slot` that has seen shreds up to `shred_slot`
+async fn node(slot: u64, shred_slot: u64) -> String {
+    let (url, _) = common::json_rpc(move |method, _| match method {
+        "getSlot" => json!(slot),
+        "getMaxShredInsertSlot" => json!(shred_slot),
+        _ => Value::Null,
+    })
+    .await;
+    url
+}
+
+// A local port that refuses connections
+async fn unreachable() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+fn pool(primary: String, secondary: Vec<String>) -> RpcPool {
+    let config = RpcConfig {
+        primary,
+        ws_url: String::new(),
+        secondary,
+        timeout_ms: 1_000,
+        retry_attempts: 2,
+        health_check_interval_ms: 0,
+        max_slot_lag: 200,
+    };
+    RpcPool::new(&config, CommitmentConfig::confirmed())
+}
+
+#[tokio::test]
+async fn unreachable_nodes_do_not_count_toward_slot_lag() {
+    let pool = pool(unreachable().await, vec![node(900, 1_000).await]);
+    assert_eq!(pool.best_slot_lag().await, None);
+
+    pool.check_health().await;
+    // The unreachable node was never polled, so its lag of 0 says nothing
+    assert_eq!(pool.best_slot_lag().await, Some(100));
+
+    let statuses = pool.statuses().await;
+    assert!(!statuses[0].health.reachable && statuses[0].health.ejected);
+    assert!(statuses[1].health.reachable && !statuses[1].health.ejected);
+}
+
+#[tokio::test]
+async fn slot_lag_is_unknown_without_a_node_in_rotation() {
+    let down = pool(unreachable().await, vec![]);
+    down.check_health().await;
+    assert_eq!(down.best_slot_lag().await, None);
+
+    // Reachable, but ejected for lagging
+    let lagging = pool(node(500, 1_000).await, vec![]);
+    lagging.check_health().await;
+    assert_eq!(lagging.best_slot_lag().await, None);
+}
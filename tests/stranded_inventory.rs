@@ -0,0 +1,75 @@
+// StrandedInventory bookkeeping: merging, partial reductions and reloading
+// the saved positions after a restart
+use chrono::Utc;
+use solana_jupiter_arbitrage_bot::stranded_inventory::{StrandedInventory, StrandedPosition};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn state_path(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("stranded_{}_{}.json", name, Pubkey::new_unique()));
+    path.to_string_lossy().into_owned()
+}
+
+fn position(mint: &str, amount: u64, cost: u64) -> StrandedPosition {
+    StrandedPosition {
+        mint: mint.to_string(),
+        quote_mint: USDC.to_string(),
+        decimals: 9,
+        amount,
+        cost,
+        stranded_at: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn positions_in_a_pair_merge_and_reduce_proportionally() {
+    let path = state_path("merge");
+    let inventory = StrandedInventory::new(path.clone());
+    let mint = Pubkey::new_unique().to_string();
+
+    inventory.add(position(&mint, 1_000, 400)).await;
+    inventory.add(position(&mint, 3_000, 1_200)).await;
+    let positions = inventory.positions().await;
+    assert_eq!(positions.len(), 1);
+    assert_eq!((positions[0].amount, positions[0].cost), (4_000, 1_600));
+
+    inventory.reduce(&mint, USDC, 1_000).await;
+    let positions = inventory.positions().await;
+    assert_eq!((positions[0].amount, positions[0].cost), (3_000, 1_200));
+
+    inventory.reduce(&mint, USDC, 3_000).await;
+    assert!(inventory.positions().await.is_empty());
+    assert!(inventory.mints().await.is_empty());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn positions_survive_a_restart() {
+    let path = state_path("restart");
+    let held = Pubkey::new_unique().to_string();
+    let sold = Pubkey::new_unique().to_string();
+    {
+        let inventory = StrandedInventory::new(path.clone());
+        inventory.add(position(&held, 5_000, 2_000)).await;
+        inventory.add(position(&sold, 1_000, 500)).await;
+        inventory.reduce(&sold, USDC, 1_000).await;
+    }
+
+    let inventory = StrandedInventory::new(path.clone());
+    assert_eq!(inventory.mints().await, HashSet::from([held.clone()]));
+    let positions = inventory.positions().await;
+    assert_eq!((positions[0].amount, positions[0].cost, positions[0].decimals), (5_000, 2_000, 9));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn a_missing_or_corrupt_file_starts_empty() {
+    let path = state_path("corrupt");
+    assert!(StrandedInventory::new(path.clone()).positions().await.is_empty());
+
+    std::fs::write(&path, "not json").unwrap();
+    assert!(StrandedInventory::new(path.clone()).positions().await.is_empty());
+    let _ = std::fs::remove_file(&path);
+}
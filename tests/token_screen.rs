@@ -0,0 +1,103 @@
+// Mint account decoding in parse_mint for SPL Token and Token-2022 layouts
+use solana_jupiter_arbitrage_bot::token_screen::parse_mint;
+use solana_jupiter_arbitrage_bot::utils::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use solana_sdk::pubkey::Pubkey;
+
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+
+// An initialized 82 byte SPL mint, with a freeze authority if given
+fn spl_mint(freeze_authority: Option<Pubkey>) -> Vec<u8> {
+    let mut data = vec![0u8; 82];
+    data[44] = 6;
+    data[45] = 1;
+    if let Some(authority) = freeze_authority {
+        data[46..50].copy_from_slice(&[1, 0, 0, 0]);
+        data[50..82].copy_from_slice(authority.as_ref());
+    }
+    data
+}
+
+// The SPL layout padded to the token account length, the mint account type,
+// then each (type, value) as a TLV extension
+fn token_2022_mint(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut data = spl_mint(None);
+    data.resize(165, 0);
+    data.push(1);
+    for (extension, value) in extensions {
+        data.extend_from_slice(&extension.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(value);
+    }
+    data
+}
+
+// TransferFeeConfig with the older and newer fee in basis points
+fn transfer_fee(older_bps: u16, newer_bps: u16) -> (u16, Vec<u8>) {
+    let mut value = vec![0u8; 108];
+    value[88..90].copy_from_slice(&older_bps.to_le_bytes());
+    value[106..108].copy_from_slice(&newer_bps.to_le_bytes());
+    (EXTENSION_TRANSFER_FEE_CONFIG, value)
+}
+
+#[test]
+fn spl_mint_reads_the_freeze_authority() {
+    let mint = Pubkey::new_unique();
+    let profile = parse_mint(mint, &TOKEN_PROGRAM_ID, &spl_mint(None)).unwrap();
+    assert!(!profile.token_2022);
+    assert_eq!(profile.decimals, 6);
+    assert_eq!(profile.freeze_authority, None);
+    assert_eq!(profile.transfer_fee_bps, None);
+    assert_eq!(profile.permanent_delegate, None);
+
+    let authority = Pubkey::new_unique();
+    let profile = parse_mint(mint, &TOKEN_PROGRAM_ID, &spl_mint(Some(authority))).unwrap();
+    assert_eq!(profile.freeze_authority, Some(authority));
+}
+
+#[test]
+fn token_2022_transfer_fee_takes_the_higher_fee() {
+    let mint = Pubkey::new_unique();
+    let data = token_2022_mint(&[transfer_fee(25, 100)]);
+    let profile = parse_mint(mint, &TOKEN_2022_PROGRAM_ID, &data).unwrap();
+    assert!(profile.token_2022);
+    assert_eq!(profile.transfer_fee_bps, Some(100));
+
+    // A scheduled drop still charges the current fee until it applies
+    let data = token_2022_mint(&[transfer_fee(50, 0)]);
+    assert_eq!(parse_mint(mint, &TOKEN_2022_PROGRAM_ID, &data).unwrap().transfer_fee_bps, Some(50));
+}
+
+#[test]
+fn token_2022_zero_transfer_fee_is_no_fee() {
+    let data = token_2022_mint(&[transfer_fee(0, 0)]);
+    let profile = parse_mint(Pubkey::new_unique(), &TOKEN_2022_PROGRAM_ID, &data).unwrap();
+    assert_eq!(profile.transfer_fee_bps, None);
+}
+
+#[test]
+fn token_2022_permanent_delegate() {
+    let delegate = Pubkey::new_unique();
+    let data = token_2022_mint(&[
+        transfer_fee(0, 0),
+        (EXTENSION_PERMANENT_DELEGATE, delegate.to_bytes().to_vec()),
+    ]);
+    let profile = parse_mint(Pubkey::new_unique(), &TOKEN_2022_PROGRAM_ID, &data).unwrap();
+    assert_eq!(profile.permanent_delegate, Some(delegate));
+
+    // A zeroed delegate means none was set
+    let data = token_2022_mint(&[(EXTENSION_PERMANENT_DELEGATE, vec![0u8; 32])]);
+    let profile = parse_mint(Pubkey::new_unique(), &TOKEN_2022_PROGRAM_ID, &data).unwrap();
+    assert_eq!(profile.permanent_delegate, None);
+}
+
+#[test]
+fn rejects_accounts_that_are_not_mints() {
+    let mint = Pubkey::new_unique();
+    assert!(parse_mint(mint, &Pubkey::new_unique(), &spl_mint(None)).is_err());
+    assert!(parse_mint(mint, &TOKEN_PROGRAM_ID, &[0u8; 40]).is_err());
+
+    let mut data = token_2022_mint(&[]);
+    data[165] = 2;
+    assert!(parse_mint(mint, &TOKEN_2022_PROGRAM_ID, &data).is_err());
+}